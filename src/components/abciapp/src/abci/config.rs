@@ -114,6 +114,10 @@ pub(crate) mod global_cfg {
         pub tendermint_node_self_addr: Option<String>,
         pub tendermint_node_key_config_path: Option<String>,
         pub ledger_dir: String,
+        pub determinism_audit: bool,
+        pub stem_peers: Vec<String>,
+        pub stem_fluff_percent: u8,
+        pub check_tx_min_fee: u64,
         #[cfg(target_os = "linux")]
         pub btmcfg: BtmCfg,
     }
@@ -142,6 +146,10 @@ pub(crate) mod global_cfg {
                 .arg_from_usage("--tendermint-node-self-addr=[Address] 'the address of your tendermint node, in upper-hex format'")
                 .arg_from_usage("--tendermint-node-key-config-path=[Path] 'such as: ${HOME}/.tendermint/config/priv_validator_key.json'")
                 .arg_from_usage("-d, --ledger-dir=[Path]")
+                .arg_from_usage("--determinism-audit 'log the hashes of the block write set and the staking state at every commit, for comparing across nodes'")
+                .arg_from_usage("--stem-peers=[Addrs] 'submission services of trusted peers, such as `10.0.0.2:8669,10.0.0.3:8669`, transactions will be relayed through them before broadcasting'")
+                .arg_from_usage("--stem-fluff-percent=[Percent] 'the probability of broadcasting directly instead of relaying to a stem peer, default to 10'")
+                .arg_from_usage("--check-tx-min-fee=[Amount] 'the minimal fee accepted by the mempool of this node, in base units of FRA(1 FRA = 10^6 units), will not be less than the consensus one'")
 
                 .arg_from_usage("--enable-snapshot 'global switch for enabling snapshot functions'")
                 .arg_from_usage("--snapshot-list 'list all available snapshots in the form of block height'")
@@ -217,6 +225,36 @@ pub(crate) mod global_cfg {
                     format!("{}/.tendermint/__findora__", pnk!(env::var("HOME")))
                 })
             });
+        let da =
+            m.is_present("determinism-audit") || env::var("DETERMINISM_AUDIT").is_ok();
+        let sp = m
+            .value_of("stem-peers")
+            .map(|v| v.to_owned())
+            .or_else(|| env::var("STEM_PEERS").ok())
+            .map(|v| {
+                v.split(',')
+                    .map(|p| p.trim().to_owned())
+                    .filter(|p| !p.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let sfp = m
+            .value_of("stem-fluff-percent")
+            .map(|v| v.to_owned())
+            .or_else(|| env::var("STEM_FLUFF_PERCENT").ok())
+            .unwrap_or_else(|| "10".to_owned())
+            .parse::<u8>()
+            .c(d!())?;
+        if 100 < sfp {
+            return Err(eg!("stem-fluff-percent should be in [0, 100]"));
+        }
+        let ctmf = m
+            .value_of("check-tx-min-fee")
+            .map(|v| v.to_owned())
+            .or_else(|| env::var("CHECK_TX_MIN_FEE").ok())
+            .unwrap_or_else(|| "0".to_owned())
+            .parse::<u64>()
+            .c(d!())?;
 
         let res = Config {
             abci_host: ah,
@@ -229,6 +267,10 @@ pub(crate) mod global_cfg {
             tendermint_node_self_addr: tnsa,
             tendermint_node_key_config_path: tnkcp,
             ledger_dir: ld,
+            determinism_audit: da,
+            stem_peers: sp,
+            stem_fluff_percent: sfp,
+            check_tx_min_fee: ctmf,
             #[cfg(target_os = "linux")]
             btmcfg: parse_btmcfg(&m).c(d!())?,
         };
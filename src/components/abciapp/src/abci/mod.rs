@@ -5,7 +5,8 @@
 #![deny(warnings)]
 #![allow(clippy::needless_borrow)]
 
-mod config;
+pub(crate) mod config;
+pub mod replay;
 mod server;
 pub mod staking;
 
@@ -42,6 +43,10 @@ pub fn run() -> Result<()> {
 
     env::set_var("BNC_DATA_DIR", format!("{}/__bnc__", &config.ledger_dir));
 
+    if CFG.determinism_audit {
+        env::set_var("FINDORAD_DETERMINISM_AUDIT", "1");
+    }
+
     let app = server::ABCISubmissionServer::new(
         basedir,
         format!("{}:{}", config.tendermint_host, config.tendermint_port),
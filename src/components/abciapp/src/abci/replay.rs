@@ -0,0 +1,143 @@
+//!
+//! # Replay
+//!
+//! Rebuild the ledger state from a stream of finalized blocks,
+//! without tendermint or any networking, so that auditors can run
+//! custom invariant checks over the full history in their own tooling.
+//!
+//! Every block goes through the same `BeginBlock`, `DeliverTx`, `EndBlock`
+//! and `Commit` logic as the ABCI application, including the staking
+//! system operations: rewards, validator updates, unbond payouts and coinbase.
+//!
+//! The input should be the blocks recorded by tendermint,
+//! ie. the original `BeginBlock` requests and the delivered transactions,
+//! the system transactions generated in `EndBlock` must NOT be included.
+//!
+//! **NOTE**: the replayer shares the process-wide tendermint height
+//! with the ABCI application, do not run it within a running node.
+//!
+
+use {
+    crate::{
+        abci::server::callback::{
+            begin_block_ops, commit_ops, deliver_tx_ops, end_block_ops,
+        },
+        api::submission_server::{SubmissionServer, TxnForward},
+    },
+    abci::RequestBeginBlock,
+    ledger::{data_model::Transaction, store::LedgerState},
+    parking_lot::RwLock,
+    rand_chacha::ChaChaRng,
+    rand_core::SeedableRng,
+    ruc::*,
+    std::sync::Arc,
+};
+
+/// A block to be replayed.
+#[derive(Clone, Debug, Default)]
+pub struct ReplayBlock {
+    /// The original `BeginBlock` request of this block,
+    /// its header, last commit info and evidences drive the staking logic.
+    pub begin_block: RequestBeginBlock,
+    /// Delivered transactions in their original order.
+    pub txns: Vec<Transaction>,
+}
+
+impl ReplayBlock {
+    /// The tendermint height of this block.
+    #[inline(always)]
+    pub fn height(&self) -> i64 {
+        self.begin_block
+            .header
+            .as_ref()
+            .map(|h| h.height)
+            .unwrap_or_default()
+    }
+}
+
+// Nothing will be sent out during replaying.
+struct NoForward;
+
+impl AsRef<str> for NoForward {
+    fn as_ref(&self) -> &str {
+        ""
+    }
+}
+
+impl TxnForward for NoForward {
+    fn forward_txn(&self, _txn: Transaction) -> Result<()> {
+        Err(eg!("forwarding is not allowed in replaying"))
+    }
+}
+
+type Check = Box<dyn FnMut(&LedgerState, &ReplayBlock) -> Result<()>>;
+
+/// Apply blocks one by one and run all the checks after each block.
+pub struct Replayer {
+    la: SubmissionServer<ChaChaRng, NoForward>,
+    checks: Vec<Check>,
+}
+
+impl Replayer {
+    /// Replay on a fresh ledger, such as `LedgerState::new(<dir>, None)`.
+    pub fn new(ledger: LedgerState) -> Result<Self> {
+        SubmissionServer::new_no_auto_commit(
+            ChaChaRng::from_entropy(),
+            Arc::new(RwLock::new(ledger)),
+            NoForward,
+        )
+        .c(d!())
+        .map(|la| Replayer { la, checks: vec![] })
+    }
+
+    /// Register an invariant check,
+    /// the replay will stop at the first block that fails it.
+    #[inline(always)]
+    pub fn add_check(
+        &mut self,
+        check: impl FnMut(&LedgerState, &ReplayBlock) -> Result<()> + 'static,
+    ) -> &mut Self {
+        self.checks.push(Box::new(check));
+        self
+    }
+
+    /// Apply a block and then run all the checks.
+    ///
+    /// Transactions that are rejected by `DeliverTx` are skipped,
+    /// just like what has happened on the chain.
+    pub fn apply_block(&mut self, b: &ReplayBlock) -> Result<()> {
+        let h = b.height();
+
+        begin_block_ops(&mut self.la, h).c(d!())?;
+        for tx in b.txns.iter() {
+            ruc::info_omit!(deliver_tx_ops(&mut self.la, tx.clone())
+                .c(d!(format!("txn rejected at height {}", h))));
+        }
+        end_block_ops(&mut self.la, &b.begin_block).c(d!())?;
+        commit_ops(&mut self.la.get_committed_state().write()).c(d!())?;
+
+        let ledger = self.la.get_committed_state().read();
+        for check in self.checks.iter_mut() {
+            check(&ledger, b).c(d!(format!("check failed at height {}", h)))?;
+        }
+
+        Ok(())
+    }
+
+    /// Replay all the blocks in order.
+    pub fn replay(
+        &mut self,
+        blocks: impl IntoIterator<Item = ReplayBlock>,
+    ) -> Result<()> {
+        for b in blocks {
+            self.apply_block(&b).c(d!())?;
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    #[allow(missing_docs)]
+    pub fn ledger(&self) -> Arc<RwLock<LedgerState>> {
+        self.la.borrowable_ledger_state()
+    }
+}
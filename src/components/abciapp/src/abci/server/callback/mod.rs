@@ -9,19 +9,26 @@ use {
         abci::{
             config::global_cfg::CFG, server::ABCISubmissionServer, staking, IN_SAFE_ITV,
         },
-        api::{query_server::BLOCK_CREATED, submission_server::convert_tx},
+        api::{
+            query_server::BLOCK_CREATED,
+            submission_server::{convert_tx, SubmissionServer, TxnForward},
+        },
     },
     abci::{
         CheckTxType, RequestBeginBlock, RequestCheckTx, RequestCommit, RequestDeliverTx,
         RequestEndBlock, RequestInfo, ResponseBeginBlock, ResponseCheckTx,
         ResponseCommit, ResponseDeliverTx, ResponseEndBlock, ResponseInfo,
     },
+    globutils::HashOf,
     lazy_static::lazy_static,
-    ledger::staking::KEEP_HIST,
+    ledger::{data_model::Transaction, staking::KEEP_HIST, store::LedgerState},
+    log::info,
     parking_lot::Mutex,
     protobuf::RepeatedField,
+    rand_core::{CryptoRng, RngCore},
     ruc::*,
     std::{
+        collections::BTreeMap,
         fs,
         ops::Deref,
         sync::{
@@ -69,12 +76,19 @@ pub fn info(s: &mut ABCISubmissionServer, _req: &RequestInfo) -> ResponseInfo {
 }
 
 /// any new tx will trigger this callback before it can enter the mem-pool of tendermint
-pub fn check_tx(_s: &mut ABCISubmissionServer, req: &RequestCheckTx) -> ResponseCheckTx {
+pub fn check_tx(s: &mut ABCISubmissionServer, req: &RequestCheckTx) -> ResponseCheckTx {
     let mut resp = ResponseCheckTx::new();
 
     if matches!(req.field_type, CheckTxType::New) {
         if let Ok(tx) = convert_tx(req.get_tx()) {
-            alt!(!tx.valid_in_abci(), resp.code = 1);
+            let fee_assets = get_fee_assets(s);
+            if !tx.valid_in_abci_with_fee_assets(&fee_assets) {
+                resp.code = 1;
+            } else if !tx.check_fee_above(&fee_assets, CFG.check_tx_min_fee) {
+                // the local limit of this node
+                resp.code = 1;
+                resp.log = String::from("Fee is lower than the minimum of this node");
+            }
         } else {
             resp.code = 1;
         }
@@ -83,6 +97,17 @@ pub fn check_tx(_s: &mut ABCISubmissionServer, req: &RequestCheckTx) -> Response
     resp
 }
 
+// whitelisted fee assets of the committed state
+#[inline(always)]
+fn get_fee_assets(s: &ABCISubmissionServer) -> BTreeMap<String, [u64; 2]> {
+    s.la.read()
+        .get_committed_state()
+        .read()
+        .get_staking()
+        .fee_assets()
+        .clone()
+}
+
 pub fn begin_block(
     s: &mut ABCISubmissionServer,
     req: &RequestBeginBlock,
@@ -110,26 +135,39 @@ pub fn begin_block(
     IN_SAFE_ITV.swap(true, Ordering::Relaxed);
 
     let header = pnk!(req.header.as_ref());
-    TENDERMINT_BLOCK_HEIGHT.swap(header.height, Ordering::Relaxed);
 
     *REQ_BEGIN_BLOCK.lock() = req.clone();
 
-    let mut la = s.la.write();
+    pnk!(begin_block_ops(&mut *s.la.write(), header.height));
+
+    ResponseBeginBlock::new()
+}
+
+/// The state changes of `BeginBlock`,
+/// shared with the offline [replayer](crate::abci::replay).
+pub(crate) fn begin_block_ops<RNG, TF>(
+    la: &mut SubmissionServer<RNG, TF>,
+    height: i64,
+) -> Result<()>
+where
+    RNG: RngCore + CryptoRng,
+    TF: TxnForward,
+{
+    TENDERMINT_BLOCK_HEIGHT.swap(height, Ordering::Relaxed);
 
     // set height first
     la.get_committed_state()
         .write()
         .get_staking_mut()
-        .set_custom_block_height(header.height as u64);
+        .set_custom_block_height(height as u64);
 
     // then create new block or update simulator
     if la.all_commited() {
         la.begin_block();
+        Ok(())
     } else {
-        pnk!(la.update_staking_simulator());
+        la.update_staking_simulator().c(d!())
     }
-
-    ResponseBeginBlock::new()
 }
 
 pub fn deliver_tx(
@@ -138,18 +176,18 @@ pub fn deliver_tx(
 ) -> ResponseDeliverTx {
     let mut resp = ResponseDeliverTx::new();
     if let Ok(tx) = convert_tx(req.get_tx()) {
-        if tx.valid_in_abci() {
-            if *KEEP_HIST {
-                // set attr(tags) if any, only needed on a fullnode
-                let attr = utils::gen_tendermint_attr(&tx);
-                if !attr.is_empty() {
-                    resp.set_events(attr);
-                }
-            }
-
-            if s.la.write().cache_transaction(tx).is_ok() {
-                return resp;
+        // set attr(tags) if any, only needed on a fullnode
+        let attr = alt!(
+            *KEEP_HIST,
+            utils::gen_tendermint_attr(&tx),
+            RepeatedField::new()
+        );
+
+        if deliver_tx_ops(&mut *s.la.write(), tx).is_ok() {
+            if !attr.is_empty() {
+                resp.set_events(attr);
             }
+            return resp;
         }
 
         resp.code = 1;
@@ -159,31 +197,73 @@ pub fn deliver_tx(
     resp
 }
 
+/// The state changes of `DeliverTx`,
+/// shared with the offline [replayer](crate::abci::replay).
+pub(crate) fn deliver_tx_ops<RNG, TF>(
+    la: &mut SubmissionServer<RNG, TF>,
+    tx: Transaction,
+) -> Result<()>
+where
+    RNG: RngCore + CryptoRng,
+    TF: TxnForward,
+{
+    let fee_assets = la
+        .get_committed_state()
+        .read()
+        .get_staking()
+        .fee_assets()
+        .clone();
+    if !tx.valid_in_abci_with_fee_assets(&fee_assets) {
+        return Err(eg!("invalid fee"));
+    }
+
+    la.cache_transaction(tx)
+        .map(|_| ())
+        .map_err(|h| eg!(format!("{} is rejected", h)))
+}
+
 /// putting block in the ledgerState
 pub fn end_block(
     s: &mut ABCISubmissionServer,
     _req: &RequestEndBlock,
 ) -> ResponseEndBlock {
-    let mut resp = ResponseEndBlock::new();
-
     let begin_block_req = REQ_BEGIN_BLOCK.lock();
-    let header = pnk!(begin_block_req.header.as_ref());
 
     IN_SAFE_ITV.swap(false, Ordering::Relaxed);
-    let mut la = s.la.write();
+
+    pnk!(end_block_ops(&mut *s.la.write(), &begin_block_req))
+}
+
+/// The state changes of `EndBlock`,
+/// shared with the offline [replayer](crate::abci::replay).
+///
+/// - mint coinbase, cache system transactions to ledger
+/// - write the block to the ledger
+/// - run the staking system operations
+pub(crate) fn end_block_ops<RNG, TF>(
+    la: &mut SubmissionServer<RNG, TF>,
+    begin_block_req: &RequestBeginBlock,
+) -> Result<ResponseEndBlock>
+where
+    RNG: RngCore + CryptoRng,
+    TF: TxnForward,
+{
+    let mut resp = ResponseEndBlock::new();
+
+    let header = begin_block_req.header.as_ref().c(d!())?;
 
     // mint coinbase, cache system transactions to ledger
     {
         let laa = la.get_committed_state().read();
         if let Some(tx) = staking::system_mint_pay(&*laa) {
             drop(laa);
-            // this unwrap should be safe
-            la.cache_transaction(tx).unwrap();
+            la.cache_transaction(tx)
+                .map_err(|h| eg!(format!("{} is rejected", h)))?;
         }
     }
 
     if !la.all_commited() && la.block_txn_count() != 0 {
-        pnk!(la.end_block());
+        la.end_block().c(d!())?;
     }
 
     if let Ok(Some(vs)) = ruc::info!(staking::get_validators(
@@ -193,26 +273,28 @@ pub fn end_block(
         resp.set_validator_updates(RepeatedField::from_vec(vs));
     }
 
+    if let Some(cp) = staking::get_consensus_params(
+        la.get_committed_state().read().get_staking().deref(),
+    ) {
+        resp.set_consensus_param_updates(cp);
+    }
+
     staking::system_ops(
         &mut *la.get_committed_state().write(),
-        &header,
+        header,
         begin_block_req.last_commit_info.as_ref(),
-        &begin_block_req.byzantine_validators.as_slice(),
+        begin_block_req.byzantine_validators.as_slice(),
     );
 
-    resp
+    Ok(resp)
 }
 
 pub fn commit(s: &mut ABCISubmissionServer, _req: &RequestCommit) -> ResponseCommit {
     let la = s.la.write();
     let mut state = la.get_committed_state().write();
 
-    // will change `struct LedgerStatus`
+    pnk!(commit_ops(&mut state));
     let td_height = TENDERMINT_BLOCK_HEIGHT.load(Ordering::Relaxed);
-    state.set_tendermint_height(td_height as u64);
-
-    // cache last block for QueryServer
-    pnk!(state.update_api_cache());
 
     // snapshot them finally
     let path = format!("{}/{}", &CFG.ledger_dir, &state.get_status().snapshot_file);
@@ -220,8 +302,34 @@ pub fn commit(s: &mut ABCISubmissionServer, _req: &RequestCommit) -> ResponseCom
         .c(d!())
         .and_then(|s| fs::write(&path, s).c(d!(path))));
 
+    // the write set tells in which block the nodes start to diverge,
+    // and the staking digest tells whether it is caused by the staking logic
+    if CFG.determinism_audit {
+        let write_set = state
+            .take_write_set_hash()
+            .map(|h| h.hex())
+            .unwrap_or_else(|| "-".to_owned());
+        let staking = HashOf::new(state.get_staking()).hex();
+        let app_hash = hex::encode(state.get_state_commitment().0);
+        info!(
+            "[determinism audit] height: {}, write set: {}, staking: {}, app hash: {}",
+            td_height, write_set, staking, app_hash
+        );
+    }
+
     let mut r = ResponseCommit::new();
     r.set_data(state.get_state_commitment().0.as_ref().to_vec());
 
     r
 }
+
+/// The state changes of `Commit`,
+/// shared with the offline [replayer](crate::abci::replay).
+pub(crate) fn commit_ops(state: &mut LedgerState) -> Result<()> {
+    // will change `struct LedgerStatus`
+    let td_height = TENDERMINT_BLOCK_HEIGHT.load(Ordering::Relaxed);
+    state.set_tendermint_height(td_height as u64);
+
+    // cache last block for QueryServer
+    state.update_api_cache().c(d!())
+}
@@ -13,7 +13,7 @@ mod real {
         ledger::data_model::{Operation, Transaction, TxnSID},
         protobuf::RepeatedField,
         serde::Serialize,
-        std::time::SystemTime,
+        std::{collections::BTreeSet, time::SystemTime},
         zei::xfr::structs::{XfrAmount, XfrAssetType},
     };
 
@@ -23,6 +23,7 @@ mod real {
     ///   - "addr.to" => "Json<TagAttr>"
     ///   - "addr.from.<addr>" => "y"
     ///   - "addr.to.<addr>" => "y"
+    ///   - "op.<kind>" => "y"
     pub fn gen_tendermint_attr(tx: &Transaction) -> RepeatedField<Event> {
         let mut res = vec![];
 
@@ -46,6 +47,28 @@ mod real {
         ev.set_attributes(RepeatedField::from_vec(kv));
         res.push(ev);
 
+        // index txs by the kinds of their operations
+        let kv = tx
+            .body
+            .operations
+            .iter()
+            .map(op_kind)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|kind| {
+                let mut p = Pair::new();
+                p.set_key(kind.as_bytes().to_vec());
+                p.set_value("y".as_bytes().to_vec());
+                p
+            })
+            .collect::<Vec<_>>();
+        if !kv.is_empty() {
+            let mut ev = Event::new();
+            ev.set_field_type("op".to_owned());
+            ev.set_attributes(RepeatedField::from_vec(kv));
+            res.push(ev);
+        }
+
         let (from, to) = gen_tendermint_attr_addr(tx);
 
         if !from.is_empty() || !to.is_empty() {
@@ -112,6 +135,14 @@ mod real {
                             base.$idx.push(attr);
                         });
                     };
+                    // staking
+                    ($data: expr, related) => {
+                        $data.get_related_pubkeys().iter().for_each(|pk| {
+                            let mut attr = TagAttr::default();
+                            attr.addr = globutils::wallet::public_key_to_bech32(pk);
+                            base.0.push(attr);
+                        });
+                    };
                     // define\issue\AIR\memo
                     ($data: expr) => {
                         let mut attr = TagAttr::default();
@@ -135,6 +166,18 @@ mod real {
                     Operation::UpdateMemo(d) => {
                         append_attr!(d);
                     }
+                    Operation::UpdateStaker(d) => {
+                        append_attr!(d, related);
+                    }
+                    Operation::Delegation(d) => {
+                        append_attr!(d, related);
+                    }
+                    Operation::UnDelegation(d) => {
+                        append_attr!(d, related);
+                    }
+                    Operation::Claim(d) => {
+                        append_attr!(d, related);
+                    }
                     _ => {}
                 }
 
@@ -142,6 +185,25 @@ mod real {
             })
     }
 
+    fn op_kind(op: &Operation) -> &'static str {
+        match op {
+            Operation::TransferAsset(_) => "TransferAsset",
+            Operation::DefineAsset(_) => "DefineAsset",
+            Operation::IssueAsset(_) => "IssueAsset",
+            Operation::UpdateMemo(_) => "UpdateMemo",
+            Operation::UpdateStaker(_) => "UpdateStaker",
+            Operation::Delegation(_) => "Delegation",
+            Operation::UnDelegation(_) => "UnDelegation",
+            Operation::Claim(_) => "Claim",
+            Operation::UpdateValidator(_) => "UpdateValidator",
+            Operation::Governance(_) => "Governance",
+            Operation::FraDistribution(_) => "FraDistribution",
+            Operation::MintFra(_) => "MintFra",
+            Operation::UpdateConsensusParams(_) => "UpdateConsensusParams",
+            Operation::UpdateFeeAsset(_) => "UpdateFeeAsset",
+        }
+    }
+
     #[derive(Serialize, Default)]
     struct TagAttr {
         // FRA address
@@ -11,12 +11,20 @@ pub use mocker::{forward_txn_with_mode, TendermintForward, CHAN};
 #[cfg(not(feature = "abci_mock"))]
 mod real {
     use {
-        crate::api::submission_server::TxnForward,
+        crate::{
+            abci::config::global_cfg::CFG,
+            api::submission_server::{TxnForward, MAX_STEM_HOPS, STEM_HOPS_HEADER},
+        },
         futures::executor::{ThreadPool, ThreadPoolBuilder},
         lazy_static::lazy_static,
         ledger::data_model::Transaction,
+        rand::{seq::SliceRandom, thread_rng, Rng},
         ruc::*,
-        std::sync::atomic::{AtomicU16, Ordering},
+        std::{
+            collections::BTreeSet,
+            net::{IpAddr, ToSocketAddrs},
+            sync::atomic::{AtomicU16, Ordering},
+        },
     };
 
     static TX_PENDING_CNT: AtomicU16 = AtomicU16::new(0);
@@ -24,6 +32,13 @@ mod real {
     lazy_static! {
         static ref POOL: ThreadPool =
             pnk!(ThreadPoolBuilder::new().pool_size(4).create());
+        static ref STEM_PEER_IPS: BTreeSet<IpAddr> = CFG
+            .stem_peers
+            .iter()
+            .filter_map(|p| ruc::info!(p.to_socket_addrs().c(d!())).ok())
+            .flatten()
+            .map(|addr| addr.ip())
+            .collect();
     }
 
     pub struct TendermintForward {
@@ -38,8 +53,70 @@ mod real {
 
     impl TxnForward for TendermintForward {
         fn forward_txn(&self, txn: Transaction) -> Result<()> {
-            forward_txn_with_mode(self.as_ref(), txn, false)
+            self.forward_relayed_txn(txn, 0)
+        }
+
+        fn forward_relayed_txn(&self, txn: Transaction, hops: u8) -> Result<()> {
+            match pick_stem_peer() {
+                Some(peer) if hops < MAX_STEM_HOPS => relay_txn_with_fallback(
+                    peer,
+                    self.as_ref().to_owned(),
+                    txn,
+                    hops + 1,
+                ),
+                _ => forward_txn_with_mode(self.as_ref(), txn, false),
+            }
+        }
+
+        fn is_stem_peer(&self, ip: &IpAddr) -> bool {
+            STEM_PEER_IPS.contains(ip)
+        }
+    }
+
+    // Dandelion-style stem phase:
+    // relay to a random trusted peer with a probability of `1 - fluff_percent`,
+    // the peer will make the same choice again, so the node that finally
+    // broadcasts the transaction is unlikely to be the one it was submitted to,
+    // a transaction that has passed `MAX_STEM_HOPS` peers is always broadcasted.
+    fn pick_stem_peer() -> Option<&'static str> {
+        if CFG.stem_peers.is_empty()
+            || thread_rng().gen_range(0..100) < CFG.stem_fluff_percent
+        {
+            return None;
+        }
+        CFG.stem_peers.choose(&mut thread_rng()).map(|p| p.as_str())
+    }
+
+    // broadcast it by ourself if the peer is unavailable
+    fn relay_txn_with_fallback(
+        peer: &str,
+        url: String,
+        txn: Transaction,
+        hops: u8,
+    ) -> Result<()> {
+        let peer = format!("http://{}/submit_transaction", peer);
+        let txn_json = serde_json::to_vec(&txn).c(d!())?;
+
+        if 2000 > TX_PENDING_CNT.fetch_add(1, Ordering::Relaxed) {
+            POOL.spawn_ok(async move {
+                let relayed = attohttpc::post(&peer)
+                    .header(attohttpc::header::CONTENT_TYPE, "application/json")
+                    .header(STEM_HOPS_HEADER, hops.to_string())
+                    .bytes(txn_json)
+                    .send()
+                    .c(d!())
+                    .and_then(|resp| resp.error_for_status().c(d!()));
+                TX_PENDING_CNT.fetch_sub(1, Ordering::Relaxed);
+                if ruc::info!(relayed).is_err() {
+                    ruc::info_omit!(forward_txn_with_mode(&url, txn, false));
+                }
+            });
+        } else {
+            TX_PENDING_CNT.fetch_sub(1, Ordering::Relaxed);
+            return Err(eg!("Too many pending tasks"));
         }
+
+        Ok(())
     }
 
     pub fn forward_txn_with_mode(
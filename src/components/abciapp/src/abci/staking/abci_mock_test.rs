@@ -42,7 +42,6 @@ use {
     rand_core::SeedableRng,
     ruc::*,
     std::collections::hash_map::DefaultHasher,
-    std::collections::HashMap,
     std::hash::Hash,
     std::ops::Deref,
     std::path::Path,
@@ -326,7 +325,7 @@ impl AbciMocker {
     fn get_delegation_power(
         &self,
         addr: &XfrPublicKey,
-    ) -> Option<HashMap<XfrPublicKey, u64>> {
+    ) -> Option<BTreeMap<XfrPublicKey, u64>> {
         self.get_delegation_by_addr(addr).map(|d| {
             d.entries
                 .iter()
@@ -334,7 +333,7 @@ impl AbciMocker {
                     let power = pnk!(self.get_validator_power(v_pk).c(d!()));
                     Some((v_pk.clone(), power))
                 })
-                .collect::<HashMap<XfrPublicKey, u64>>()
+                .collect::<BTreeMap<XfrPublicKey, u64>>()
         })
     }
 }
@@ -1352,8 +1351,168 @@ fn staking_scene_2() -> Result<()> {
     Ok(())
 }
 
+// Delegation Lifecycle
+//
+// 0. issue FRA
+// 1. update validators and do self-delegations
+// 2. transfer some FRAs to a new addr `x`
+// 3. use `x` to propose a delegation
+// 4. make sure delegation rewards are accrued block by block
+// 5. claim part of the rewards
+// 6. undelegate, and make sure the delegation is in its unbond period
+// 7. make sure nothing is paid before the end of the unbond period
+// 8. make sure the principal and all the rewards are paid by coinbase
+// 9. make sure a paid delegation can not be claimed any more
+fn staking_scene_3() -> Result<()> {
+    const VALIDATORS_NUM: u8 = 5;
+
+    env_refresh(VALIDATORS_NUM);
+
+    // 0. issue FRA
+
+    let tx = fra_gen_initial_tx(&ROOT_KEYPAIR);
+    let tx_hash = gen_tx_hash(&tx);
+    send_tx(tx).c(d!())?;
+    wait_n_block(5);
+    assert!(is_successful(&tx_hash));
+
+    // 1. update validators and do self-delegations
+
+    let (v_set, kps) = gen_new_validators(VALIDATORS_NUM);
+    assert_eq!(v_set.len(), kps.len());
+
+    let initial_keypairs = INITIAL_KEYPAIR_LIST.iter().collect::<Vec<_>>();
+    let tx_hash =
+        update_validator(&ROOT_KEYPAIR, &initial_keypairs, 2, v_set.clone()).c(d!())?;
+    wait_one_block();
+    assert!(is_successful(&tx_hash));
+
+    // validators will be updated every 4 blocks
+    trigger_next_block!(3);
+
+    for (i, kp) in kps.iter().enumerate() {
+        let tx_hash = transfer(&ROOT_KEYPAIR, &v_set[i].id, 1_0001 * FRA).c(d!())?;
+        wait_n_block(5);
+        assert!(is_successful(&tx_hash));
+
+        let tx_hash =
+            delegate(kp, td_pubkey_to_td_addr(&v_set[i].td_pubkey), 1_0000 * FRA)
+                .c(d!())?;
+        wait_n_block(5);
+        assert!(is_successful(&tx_hash));
+    }
+
+    // 2. transfer some FRAs to a new addr `x`
+
+    let x_kp = gen_keypair();
+    let x_pk = x_kp.get_pk();
+
+    let tx_hash = transfer(&ROOT_KEYPAIR, &x_pk, 1000 * FRA).c(d!())?;
+    wait_one_block();
+    assert!(is_successful(&tx_hash));
+    assert_eq!(1000 * FRA, ABCI_MOCKER.read().get_owned_balance(&x_pk));
+
+    // 3. use `x` to propose a delegation
+
+    let principal = 500 * FRA;
+    let tx_hash =
+        delegate(&x_kp, td_pubkey_to_td_addr(&v_set[0].td_pubkey), principal).c(d!())?;
+    wait_one_block();
+    assert!(is_successful(&tx_hash));
+
+    assert_eq!(
+        1000 * FRA - principal - TX_FEE_MIN,
+        ABCI_MOCKER.read().get_owned_balance(&x_pk)
+    );
+    assert_eq!(
+        principal,
+        ABCI_MOCKER.read().get_owned_delegation_principal(&x_pk)
+    );
+
+    // 4. make sure delegation rewards are accrued block by block
+
+    let mut last_rwd = ABCI_MOCKER.read().get_owned_reward(&x_pk);
+    for _ in 0..3 {
+        trigger_next_block!();
+        let rwd = ABCI_MOCKER.read().get_owned_reward(&x_pk);
+        assert!(rwd > last_rwd);
+        last_rwd = rwd;
+    }
+
+    // 5. claim part of the rewards
+
+    let old_balance = ABCI_MOCKER.read().get_owned_balance(&x_pk);
+    let tx_hash = claim(&x_kp, 1).c(d!())?;
+    wait_one_block();
+    assert!(is_successful(&tx_hash));
+
+    // waiting to be paid
+    trigger_next_block!();
+
+    assert_eq!(
+        old_balance - TX_FEE_MIN + 1,
+        ABCI_MOCKER.read().get_owned_balance(&x_pk)
+    );
+
+    // 6. undelegate, and make sure the delegation is in its unbond period
+
+    let old_balance = ABCI_MOCKER.read().get_owned_balance(&x_pk);
+    let tx_hash = undelegate(&x_kp).c(d!())?;
+    wait_one_block();
+    assert!(is_successful(&tx_hash));
+
+    {
+        let hdr = ABCI_MOCKER.read();
+        let hdr = hdr.0.la.read();
+        let hdr = hdr.get_committed_state().read();
+
+        let staking = hdr.get_staking();
+        let d = staking.delegation_get(&x_pk).c(d!())?;
+
+        assert_eq!(DelegationState::Bond, d.state);
+        assert!(d.end_height > staking.cur_height());
+        assert!(d.end_height - staking.cur_height() <= UNBOND_BLOCK_CNT);
+    }
+
+    let old_balance = old_balance - TX_FEE_MIN;
+    assert_eq!(old_balance, ABCI_MOCKER.read().get_owned_balance(&x_pk));
+
+    // 7. make sure nothing is paid before the end of the unbond period
+
+    trigger_next_block!(UNBOND_BLOCK_CNT / 2);
+
+    assert_eq!(old_balance, ABCI_MOCKER.read().get_owned_balance(&x_pk));
+    assert_eq!(
+        principal,
+        ABCI_MOCKER.read().get_owned_delegation_principal(&x_pk)
+    );
+
+    // 8. make sure the principal and all the rewards are paid by coinbase
+
+    let rwd = ABCI_MOCKER.read().get_owned_reward(&x_pk);
+    let rwd_limit =
+        calculate_delegation_rewards(principal, ABCI_MOCKER.read().get_current_rate())
+            .c(d!())?
+            * (2 * UNBOND_BLOCK_CNT + 10);
+
+    trigger_next_block!(1 + UNBOND_BLOCK_CNT);
+
+    let new_balance = ABCI_MOCKER.read().get_owned_balance(&x_pk);
+    assert!(old_balance + principal + rwd <= new_balance);
+    assert!(old_balance + principal + rwd + rwd_limit >= new_balance);
+
+    // 9. make sure a paid delegation can not be claimed any more
+
+    let tx_hash = claim(&x_kp, 1).c(d!())?;
+    wait_one_block();
+    assert!(is_failed(&tx_hash));
+
+    Ok(())
+}
+
 #[test]
 fn staking_integration_abci_mock() {
     pnk!(staking_scene_1());
     pnk!(staking_scene_2());
+    pnk!(staking_scene_3());
 }
@@ -15,7 +15,10 @@ pub mod abci_mock_test;
 
 use {
     crate::abci::server::callback::TENDERMINT_BLOCK_HEIGHT,
-    abci::{Evidence, Header, LastCommitInfo, PubKey, ValidatorUpdate},
+    abci::{
+        BlockParams, ConsensusParams, Evidence, Header, LastCommitInfo, PubKey,
+        ValidatorUpdate,
+    },
     lazy_static::lazy_static,
     ledger::{
         data_model::{Operation, Transaction, ASSET_TYPE_FRA},
@@ -147,6 +150,23 @@ pub fn get_validators(
     ))
 }
 
+/// Get the consensus params that are scheduled
+/// by on-chain governance for current block height, if any.
+///
+/// Consensus params returned for block H apply for block H+1.
+pub fn get_consensus_params(staking: &Staking) -> Option<ConsensusParams> {
+    staking
+        .consensus_params_get_at_height(staking.cur_height())
+        .map(|cp| {
+            let mut bp = BlockParams::new();
+            bp.set_max_bytes(cp.max_bytes);
+            bp.set_max_gas(cp.max_gas);
+            let mut params = ConsensusParams::new();
+            params.set_block(bp);
+            params
+        })
+}
+
 /// Call this function in `EndBlock`,
 /// - pay delegation rewards
 /// - pay proposer rewards(traditional block rewards)
@@ -1,13 +1,18 @@
 #![allow(missing_docs)]
 
 use {
+    crate::abci::replay::{ReplayBlock, Replayer},
+    abci::{Header, RequestBeginBlock},
     finutils::txn_builder::{TransactionBuilder, TransferOperationBuilder},
     ledger::{
         data_model::{
-            Transaction, TransferType, TxnEffect, TxoRef, ASSET_TYPE_FRA,
+            Operation, Transaction, TransferType, TxnEffect, TxoRef, ASSET_TYPE_FRA,
             BLACK_HOLE_PUBKEY, TX_FEE_MIN,
         },
-        staking::{FF_PK_LIST, FRA_PRE_ISSUE_AMOUNT},
+        staking::{
+            ops::update_validator::UpdateValidatorOps, Validator, ValidatorKind,
+            FF_PK_LIST, FRA_PRE_ISSUE_AMOUNT,
+        },
         store::{utils::fra_gen_initial_tx, LedgerState},
     },
     rand::random,
@@ -90,6 +95,111 @@ fn check_block_rewards_rate() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn staking_replay_end_block() {
+    pnk!(check_replay_end_block());
+}
+
+// 1. issue FRAs
+// 2. set the initial validators at height 3
+// 3. update the validators at height 5 with different powers
+// 4. the powers of step 2 must be inherited in the `EndBlock` of height 5
+fn check_replay_end_block() -> Result<()> {
+    const POWER: u64 = 100;
+
+    let mut replayer = Replayer::new(LedgerState::tmp_ledger()).c(d!())?;
+    let root_kp = XfrKeyPair::generate(&mut ChaChaRng::from_entropy());
+
+    let kps = (0..5)
+        .map(|_| XfrKeyPair::generate(&mut ChaChaRng::from_entropy()))
+        .collect::<Vec<_>>();
+    let gen_vs = |power| {
+        kps.iter()
+            .enumerate()
+            .map(|(i, kp)| {
+                Validator::new(
+                    vec![i as u8; 32],
+                    power,
+                    kp.get_pk(),
+                    [1, 100],
+                    Default::default(),
+                    ValidatorKind::Initor,
+                )
+                .c(d!())
+            })
+            .collect::<Result<Vec<_>>>()
+    };
+
+    let tx = fra_gen_initial_tx(&root_kp);
+    replayer
+        .apply_block(&gen_replay_block(1, vec![tx]))
+        .c(d!())?;
+
+    let tx = gen_update_validator_tx(&replayer, &root_kp, &[], 3, gen_vs(POWER)?)?;
+    replayer
+        .apply_block(&gen_replay_block(2, vec![tx]))
+        .c(d!())?;
+
+    let cosig_kps = kps.iter().collect::<Vec<_>>();
+    let tx =
+        gen_update_validator_tx(&replayer, &root_kp, &cosig_kps, 5, gen_vs(1 + POWER)?)?;
+    replayer
+        .apply_block(&gen_replay_block(3, vec![tx]))
+        .c(d!())?;
+
+    // empty blocks also go through `EndBlock`
+    replayer.apply_block(&gen_replay_block(4, vec![])).c(d!())?;
+    replayer.apply_block(&gen_replay_block(5, vec![])).c(d!())?;
+
+    let ledger = replayer.ledger();
+    let la = ledger.read();
+    let staking = la.get_staking();
+    assert_eq!(5, staking.cur_height());
+
+    let vd = staking.validator_get_current().c(d!())?;
+    assert_eq!(5, vd.body.len());
+    assert!(vd.body.values().all(|v| POWER == v.td_power));
+
+    Ok(())
+}
+
+fn gen_replay_block(h: i64, txns: Vec<Transaction>) -> ReplayBlock {
+    let mut header = Header::new();
+    header.set_height(h);
+
+    let mut begin_block = RequestBeginBlock::new();
+    begin_block.set_header(header);
+
+    ReplayBlock { begin_block, txns }
+}
+
+fn gen_update_validator_tx(
+    replayer: &Replayer,
+    owner_kp: &XfrKeyPair,
+    cosig_kps: &[&XfrKeyPair],
+    h: u64,
+    vs: Vec<Validator>,
+) -> Result<Transaction> {
+    let ledger = replayer.ledger();
+    let la = ledger.read();
+
+    // pay the fee to self
+    let mut tx = gen_transfer_tx(
+        &la,
+        owner_kp,
+        owner_kp.get_pk_ref(),
+        1,
+        la.get_state_commitment().1,
+    )
+    .c(d!())?;
+
+    UpdateValidatorOps::new(cosig_kps, h, vs, tx.body.no_replay_token)
+        .c(d!())
+        .map(|op| tx.add_operation(Operation::UpdateValidator(op)))?;
+
+    Ok(tx)
+}
+
 fn gen_transfer_tx(
     la: &LedgerState,
     owner_kp: &XfrKeyPair,
@@ -2,6 +2,9 @@
 //! # Services provided by api
 //!
 
+// out of consensus, SEE `clippy.toml` of this crate
+#![allow(clippy::disallowed_types)]
+
 /// Provide query service for ledgerState
 pub mod query_server;
 
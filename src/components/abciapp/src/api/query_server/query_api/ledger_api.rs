@@ -3,28 +3,39 @@
 //!
 
 use {
-    super::server::QueryServer,
-    actix_web::{error, web},
+    super::server::{unix_timestamp, QueryServer},
+    crate::abci::config::global_cfg::CFG,
+    actix_web::{error, web, HttpRequest, HttpResponse},
     finutils::api::{
-        DelegationInfo, DelegatorInfo, DelegatorList, NetworkRoute, Validator,
-        ValidatorDetail, ValidatorList,
+        ChainParam, ChainParams, DelegationInfo, DelegatorInfo, DelegatorList,
+        NetworkRoute, OwnershipAttestation, OwnershipChallenge,
+        OwnershipChallengeRequest, OwnershipProof, ParamSource, Validator,
+        ValidatorDetail, ValidatorEarnings, ValidatorList,
     },
-    globutils::HashOf,
+    globutils::{wallet, HashOf},
     ledger::{
         data_model::{
             AssetType, AssetTypeCode, AuthenticatedUtxo, StateCommitmentData, TxnSID,
-            TxoSID, UnAuthenticatedUtxo, Utxo,
+            TxoSID, UnAuthenticatedUtxo, Utxo, ASSET_TYPE_FRA, FRA_DECIMALS, TX_FEE_MIN,
         },
         staking::{
-            DelegationRwdDetail, DelegationState, Staking, TendermintAddr,
-            TendermintAddrRef, UNBOND_BLOCK_CNT,
+            DelegationRwdDetail, DelegationState, RewardRecord, Staking, TendermintAddr,
+            TendermintAddrRef, BLOCK_INTERVAL, COMMISSION_RATE_MAX,
+            COMMISSION_RATE_MAX_CHANGE, COMMISSION_RATE_UPDATE_BLOCK_ITV,
+            COMMISSION_RATE_UPDATE_CHECK_HEIGHT, FRA_PRE_ISSUE_AMOUNT, FRA_TOTAL_AMOUNT,
+            MAX_DELEGATION_AMOUNT, MAX_POWER_PERCENT_PER_VALIDATOR, MAX_TOTAL_POWER,
+            MIN_DELEGATION_AMOUNT, PROPOSER_REWARDS_RATE_RULE,
+            STAKING_VALIDATOR_MIN_POWER, UNBOND_BLOCK_CNT, VALIDATOR_UPDATE_BLOCK_ITV,
         },
     },
     parking_lot::RwLock,
     ruc::*,
     serde::{Deserialize, Serialize},
-    std::{collections::BTreeMap, mem, sync::Arc},
-    zei::xfr::{sig::XfrPublicKey, structs::OwnerMemo},
+    std::{cmp::max, collections::BTreeMap, mem, sync::Arc},
+    zei::xfr::{
+        sig::XfrPublicKey,
+        structs::{OwnerMemo, XfrAmount, XfrAssetType},
+    },
 };
 
 /// Ping route to check for liveness of API
@@ -169,6 +180,27 @@ pub async fn query_txn(
     }
 }
 
+/// query tx according to its tendermint hash,
+/// the result is the same as `query_txn_light`
+pub async fn query_txn_by_hash(
+    data: web::Data<Arc<RwLock<QueryServer>>>,
+    info: web::Path<String>,
+) -> actix_web::Result<String> {
+    let qs = data.read();
+    let hash = info.trim_start_matches("0x").to_uppercase();
+    if let Some(mut txn) = qs
+        .get_transaction_sid(hash)
+        .and_then(|sid| ruc::info!(qs.ledger_cloned.get_transaction_light(sid)).ok())
+    {
+        txn.set_txo_id();
+        Ok(serde_json::to_string(&txn)?)
+    } else {
+        Err(actix_web::error::ErrorNotFound(
+            "Specified transaction does not exist.",
+        ))
+    }
+}
+
 /// query tx according to `TxnSID`, lighter and faster version
 pub async fn query_txn_light(
     data: web::Data<Arc<RwLock<QueryServer>>>,
@@ -299,6 +331,79 @@ pub async fn get_delegation_reward(
     ))
 }
 
+#[allow(missing_docs)]
+#[derive(Deserialize, Debug)]
+pub struct RewardRecordsQueryParams {
+    address: String,
+    start_height: Option<u64>,
+    end_height: Option<u64>,
+    /// "json" or "csv", default to "json"
+    format: Option<String>,
+}
+
+/// get all reward payouts of a delegator within a range of block heights,
+/// can be downloaded as a CSV file for tax reports
+pub async fn get_reward_records(
+    data: web::Data<Arc<RwLock<QueryServer>>>,
+    web::Query(info): web::Query<RewardRecordsQueryParams>,
+) -> actix_web::Result<HttpResponse> {
+    let key: XfrPublicKey = globutils::wallet::public_key_from_base64(&info.address)
+        .c(d!())
+        .map_err(|e| error::ErrorBadRequest(e.generate_log(None)))?;
+
+    let start = info.start_height.unwrap_or(0);
+    let end = info.end_height.unwrap_or(u64::MAX);
+    if start > end {
+        return Err(error::ErrorBadRequest(
+            "start_height is bigger than end_height",
+        ));
+    }
+
+    let qs = data.read();
+    let mut records = qs
+        .ledger_cloned
+        .api_cache
+        .staking_rwd_records
+        .get(&key)
+        .map(|hist| {
+            hist.iter()
+                .filter(|(h, _)| (start..=end).contains(h))
+                .flat_map(|(_, rs)| rs)
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    records.sort_by_key(|r| r.block_height);
+
+    match info.format.as_deref() {
+        None | Some("json") => Ok(HttpResponse::Ok().json(records)),
+        Some("csv") => Ok(HttpResponse::Ok()
+            .content_type("text/csv")
+            .header(
+                "Content-Disposition",
+                format!("attachment; filename=\"rewards_{}.csv\"", info.address),
+            )
+            .body(reward_records_to_csv(&records))),
+        _ => Err(error::ErrorBadRequest(
+            "unsupported format, only `json` and `csv` are acceptable",
+        )),
+    }
+}
+
+#[inline(always)]
+fn reward_records_to_csv(records: &[RewardRecord]) -> String {
+    records
+        .iter()
+        .fold("block_height,kind,amount\n".to_owned(), |mut csv, r| {
+            csv.push_str(&format!(
+                "{},{},{}\n",
+                r.block_height,
+                r.kind.as_str(),
+                r.amount
+            ));
+            csv
+        })
+}
+
 #[allow(missing_docs)]
 #[derive(Deserialize, Debug)]
 pub struct ValidatorDelegationQueryParams {
@@ -553,6 +658,111 @@ pub async fn query_validator_detail(
     Err(error::ErrorNotFound("not exists"))
 }
 
+/// query cumulative earnings and the current share of voting power of a validator,
+/// the totals only cover rewards paid since this node enabled `KEEP_HIST`
+pub async fn query_validator_earnings(
+    data: web::Data<Arc<RwLock<QueryServer>>>,
+    addr: web::Path<TendermintAddr>,
+) -> actix_web::Result<web::Json<ValidatorEarnings>> {
+    let qs = data.read();
+    let ledger = &qs.ledger_cloned;
+    let staking = ledger.get_staking();
+
+    let v_id = info!(staking.validator_td_addr_to_app_pk(addr.as_ref()))
+        .map_err(error::ErrorBadRequest)?;
+
+    let v = staking
+        .validator_get_current_one_by_id(&v_id)
+        .ok_or_else(|| error::ErrorNotFound("not exists"))?;
+
+    let totals = ledger
+        .api_cache
+        .staking_rwd_totals
+        .get(&v_id)
+        .unwrap_or_default();
+
+    Ok(web::Json(ValidatorEarnings {
+        addr: addr.into_inner(),
+        commission_total: totals.commission,
+        self_delegation_rwd_total: totals.delegation,
+        proposer_rwd_total: totals.proposer,
+        voting_power: v.td_power,
+        voting_power_share: [v.td_power, staking.validator_global_power()],
+        delegator_cnt: v.delegators.len() as u64,
+        cur_height: staking.cur_height(),
+    }))
+}
+
+/// query all consensus-relevant parameters, together with their sources
+pub async fn query_chain_params(
+    data: web::Data<Arc<RwLock<QueryServer>>>,
+) -> actix_web::Result<web::Json<ChainParams>> {
+    use ParamSource::*;
+
+    let qs = data.read();
+    let ledger = &qs.ledger_cloned;
+    let staking = ledger.get_staking();
+    let cp = staking.consensus_params_get_effective();
+
+    Ok(web::Json(ChainParams {
+        cur_height: staking.cur_height(),
+        tx_fee_min: ChainParam::new(TX_FEE_MIN, CompileTime),
+        fra_decimals: ChainParam::new(FRA_DECIMALS, CompileTime),
+        fra_pre_issue_amount: ChainParam::new(FRA_PRE_ISSUE_AMOUNT, CompileTime),
+        fra_total_amount: ChainParam::new(FRA_TOTAL_AMOUNT, CompileTime),
+        block_interval: ChainParam::new(BLOCK_INTERVAL, CompileTime),
+        unbond_block_cnt: ChainParam::new(UNBOND_BLOCK_CNT, CompileTime),
+        min_delegation_amount: ChainParam::new(MIN_DELEGATION_AMOUNT, CompileTime),
+        max_delegation_amount: ChainParam::new(MAX_DELEGATION_AMOUNT, CompileTime),
+        staking_validator_min_power: ChainParam::new(
+            STAKING_VALIDATOR_MIN_POWER,
+            CompileTime,
+        ),
+        max_power_percent_per_validator: ChainParam::new(
+            MAX_POWER_PERCENT_PER_VALIDATOR,
+            CompileTime,
+        ),
+        max_total_power: ChainParam::new(MAX_TOTAL_POWER, CompileTime),
+        validator_update_block_itv: ChainParam::new(
+            VALIDATOR_UPDATE_BLOCK_ITV,
+            CompileTime,
+        ),
+        proposer_rewards_rate_rule: ChainParam::new(
+            PROPOSER_REWARDS_RATE_RULE.to_vec(),
+            CompileTime,
+        ),
+        block_rewards_rate: ChainParam::new(
+            ledger.staking_get_block_rewards_rate(),
+            Dynamic,
+        ),
+        global_delegation_percent: ChainParam::new(
+            ledger.staking_get_global_delegation_percent(),
+            Dynamic,
+        ),
+        cosig_threshold: ChainParam::new(staking.get_cosig_rule().threshold, Governance),
+        block_max_bytes: ChainParam::new(cp.map(|cp| cp.max_bytes), Governance),
+        block_max_gas: ChainParam::new(cp.map(|cp| cp.max_gas), Governance),
+        fee_assets: ChainParam::new(staking.fee_assets().clone(), Governance),
+        check_tx_min_fee: ChainParam::new(
+            max(TX_FEE_MIN, CFG.check_tx_min_fee),
+            NodeLocal,
+        ),
+        commission_rate_max: ChainParam::new(COMMISSION_RATE_MAX, CompileTime),
+        commission_rate_max_change: ChainParam::new(
+            COMMISSION_RATE_MAX_CHANGE,
+            CompileTime,
+        ),
+        commission_rate_update_block_itv: ChainParam::new(
+            COMMISSION_RATE_UPDATE_BLOCK_ITV,
+            CompileTime,
+        ),
+        commission_rate_update_check_height: ChainParam::new(
+            COMMISSION_RATE_UPDATE_CHECK_HEIGHT,
+            CompileTime,
+        ),
+    }))
+}
+
 /// query delegation info according to `public_key`
 pub async fn query_delegation_info(
     data: web::Data<Arc<RwLock<QueryServer>>>,
@@ -656,6 +866,82 @@ pub async fn query_owned_utxos(
         .map(|pk| web::Json(pnk!(ledger.get_owned_utxos(&pk))))
 }
 
+/// Issue a one-time challenge for proving the ownership of an address,
+/// the address is a `XfrPublicKey` in base64 format.
+pub async fn issue_ownership_challenge(
+    req: HttpRequest,
+    data: web::Data<Arc<RwLock<QueryServer>>>,
+    web::Json(body): web::Json<OwnershipChallengeRequest>,
+) -> actix_web::Result<web::Json<OwnershipChallenge>> {
+    let pk = wallet::public_key_from_base64(&body.address)
+        .c(d!())
+        .map_err(|e| error::ErrorBadRequest(e.generate_log(None)))?;
+    let address = wallet::public_key_to_base64(&pk);
+    let client = req
+        .peer_addr()
+        .map(|a| a.ip().to_string())
+        .unwrap_or_default();
+
+    let (nonce, expire_at) = data.write().issue_ownership_challenge(&pk, &client);
+
+    Ok(web::Json(OwnershipChallenge {
+        message: wallet::ownership_challenge_message(&address, &nonce),
+        address,
+        nonce,
+        expire_at,
+    }))
+}
+
+/// Verify the signature of a wallet on an ownership challenge,
+/// and report the current holdings of the address.
+///
+/// **NOTE**: the report is not signed by the node,
+/// it can only be trusted by the caller itself over a trusted connection.
+pub async fn verify_ownership_proof(
+    data: web::Data<Arc<RwLock<QueryServer>>>,
+    web::Json(proof): web::Json<OwnershipProof>,
+) -> actix_web::Result<web::Json<OwnershipAttestation>> {
+    let pk = wallet::public_key_from_base64(&proof.address)
+        .c(d!())
+        .map_err(|e| error::ErrorBadRequest(e.generate_log(None)))?;
+
+    let mut qs = data.write();
+    qs.take_ownership_challenge(&proof.nonce, &pk)
+        .c(d!())
+        .map_err(|e| error::ErrorBadRequest(e.generate_log(None)))?;
+    wallet::verify_ownership_challenge(&pk, &proof.nonce, &proof.signature)
+        .c(d!())
+        .map_err(|e| error::ErrorUnauthorized(e.generate_log(None)))?;
+
+    let ledger = &qs.ledger_cloned;
+    let utxos = ledger
+        .get_owned_utxos(&pk)
+        .c(d!())
+        .map_err(|e| error::ErrorInternalServerError(e.generate_log(None)))?;
+    let fra_balance = utxos
+        .values()
+        .filter_map(|(utxo, _)| {
+            match (&utxo.0.record.asset_type, &utxo.0.record.amount) {
+                (XfrAssetType::NonConfidential(t), XfrAmount::NonConfidential(am))
+                    if ASSET_TYPE_FRA == *t =>
+                {
+                    Some(*am)
+                }
+                _ => None,
+            }
+        })
+        .sum();
+
+    Ok(web::Json(OwnershipAttestation {
+        address: wallet::public_key_to_base64(&pk),
+        nonce: proof.nonce,
+        block_height: ledger.get_staking().cur_height(),
+        verified_at: unix_timestamp(),
+        owned_utxo_cnt: utxos.len() as u64,
+        fra_balance,
+    }))
+}
+
 #[allow(missing_docs)]
 pub enum ApiRoutes {
     UtxoSid,
@@ -666,12 +952,17 @@ pub enum ApiRoutes {
     GlobalState,
     TxnSid,
     TxnSidLight,
+    TxnByHash,
     GlobalStateVersion,
     OwnedUtxos,
     ValidatorList,
     DelegationInfo,
     DelegatorList,
     ValidatorDetail,
+    ValidatorEarnings,
+    ChainParams,
+    OwnershipChallenge,
+    OwnershipProof,
 }
 
 impl NetworkRoute for ApiRoutes {
@@ -685,12 +976,17 @@ impl NetworkRoute for ApiRoutes {
             ApiRoutes::GlobalState => "global_state",
             ApiRoutes::TxnSid => "txn_sid",
             ApiRoutes::TxnSidLight => "txn_sid_light",
+            ApiRoutes::TxnByHash => "txn_by_hash",
             ApiRoutes::GlobalStateVersion => "global_state_version",
             ApiRoutes::OwnedUtxos => "owned_utxos",
             ApiRoutes::ValidatorList => "validator_list",
             ApiRoutes::DelegationInfo => "delegation_info",
             ApiRoutes::DelegatorList => "delegator_list",
             ApiRoutes::ValidatorDetail => "validator_detail",
+            ApiRoutes::ValidatorEarnings => "validator_earnings",
+            ApiRoutes::ChainParams => "chain_params",
+            ApiRoutes::OwnershipChallenge => "ownership_challenge",
+            ApiRoutes::OwnershipProof => "ownership_proof",
         };
         "/".to_owned() + endpoint
     }
@@ -25,7 +25,10 @@ use {
     ruc::*,
     serde::{Deserialize, Serialize},
     server::QueryServer,
-    std::{collections::HashSet, sync::Arc},
+    std::{
+        collections::{BTreeSet, HashSet},
+        sync::Arc,
+    },
     zei::{
         serialization::ZeiFromToBytes,
         xfr::{sig::XfrPublicKey, structs::OwnerMemo},
@@ -372,7 +375,7 @@ pub async fn get_claim_txns(
 pub async fn get_related_txns(
     data: web::Data<Arc<RwLock<QueryServer>>>,
     info: web::Path<String>,
-) -> actix_web::Result<web::Json<HashSet<TxnSID>>> {
+) -> actix_web::Result<web::Json<BTreeSet<TxnSID>>> {
     // Convert from base64 representation
     let key: XfrPublicKey = XfrPublicKey::zei_from_bytes(
         &b64dec(&*info)
@@ -390,7 +393,7 @@ pub async fn get_related_txns(
 pub async fn get_related_xfrs(
     data: web::Data<Arc<RwLock<QueryServer>>>,
     info: web::Path<String>,
-) -> actix_web::Result<web::Json<HashSet<TxnSID>>> {
+) -> actix_web::Result<web::Json<BTreeSet<TxnSID>>> {
     let server = data.read();
     if let Ok(token_code) = AssetTypeCode::new_from_base64(&*info) {
         if let Some(records) = server.get_related_transfers(&token_code) {
@@ -518,6 +521,10 @@ impl QueryApi {
                     &ApiRoutes::TxnSidLight.with_arg_template("sid"),
                     web::get().to(query_txn_light),
                 )
+                .route(
+                    &ApiRoutes::TxnByHash.with_arg_template("hash"),
+                    web::get().to(query_txn_by_hash),
+                )
                 .route(
                     &ApiRoutes::GlobalStateVersion.with_arg_template("version"),
                     web::get().to(query_global_state_version),
@@ -550,10 +557,30 @@ impl QueryApi {
                     web::resource("/validator_delegation")
                         .route(web::get().to(get_validator_delegation_history)),
                 )
+                .service(
+                    web::resource("/reward_records")
+                        .route(web::get().to(get_reward_records)),
+                )
                 .route(
                     &ApiRoutes::ValidatorDetail.with_arg_template("NodeAddress"),
                     web::get().to(query_validator_detail),
                 )
+                .route(
+                    &ApiRoutes::ValidatorEarnings.with_arg_template("NodeAddress"),
+                    web::get().to(query_validator_earnings),
+                )
+                .route(
+                    &ApiRoutes::ChainParams.route(),
+                    web::get().to(query_chain_params),
+                )
+                .route(
+                    &ApiRoutes::OwnershipChallenge.route(),
+                    web::post().to(issue_ownership_challenge),
+                )
+                .route(
+                    &ApiRoutes::OwnershipProof.route(),
+                    web::post().to(verify_ownership_proof),
+                )
         });
 
         for (host, port) in addrs.iter() {
@@ -3,6 +3,7 @@
 //!
 
 use {
+    globutils::wallet,
     lazy_static::lazy_static,
     ledger::{
         data_model::{
@@ -14,10 +15,27 @@ use {
     },
     parking_lot::{Condvar, Mutex, RwLock},
     ruc::*,
-    std::{collections::HashSet, sync::Arc},
-    zei::xfr::structs::OwnerMemo,
+    std::{
+        collections::{BTreeMap, BTreeSet, VecDeque},
+        sync::Arc,
+        time::{SystemTime, UNIX_EPOCH},
+    },
+    zei::xfr::{sig::XfrPublicKey, structs::OwnerMemo},
 };
 
+// An ownership challenge is valid for 5 minutes.
+const OWNERSHIP_CHALLENGE_TTL: u64 = 300;
+
+// Upper limit of the pending ownership challenges,
+// the oldest one will be evicted when it is reached.
+const OWNERSHIP_CHALLENGE_MAX: usize = 10_0000;
+
+// Upper limit of the pending ownership challenges of an address.
+const OWNERSHIP_CHALLENGE_MAX_PER_ADDR: usize = 4;
+
+// Upper limit of the pending ownership challenges of a client.
+const OWNERSHIP_CHALLENGE_MAX_PER_CLIENT: usize = 16;
+
 lazy_static! {
     /// the query_server will be notified every time
     /// a block is added to the ledgerState to update the data
@@ -29,6 +47,7 @@ lazy_static! {
 pub struct QueryServer {
     pub(crate) ledger: Arc<RwLock<LedgerState>>,
     pub(crate) ledger_cloned: LedgerState,
+    ownership_challenges: OwnershipChallenges,
 }
 
 impl QueryServer {
@@ -38,9 +57,35 @@ impl QueryServer {
         QueryServer {
             ledger,
             ledger_cloned,
+            ownership_challenges: OwnershipChallenges::default(),
         }
     }
 
+    /// Issue a one-time nonce for proving the ownership of `pk` to `client`,
+    /// return `(nonce, expire_at)`.
+    #[inline(always)]
+    pub fn issue_ownership_challenge(
+        &mut self,
+        pk: &XfrPublicKey,
+        client: &str,
+    ) -> (String, u64) {
+        self.ownership_challenges
+            .issue(pk, client, unix_timestamp())
+    }
+
+    /// Consume a nonce issued by `issue_ownership_challenge`,
+    /// a nonce can not be used twice whether the proof is valid or not.
+    #[inline(always)]
+    pub fn take_ownership_challenge(
+        &mut self,
+        nonce: &str,
+        pk: &XfrPublicKey,
+    ) -> Result<()> {
+        self.ownership_challenges
+            .take(nonce, pk, unix_timestamp())
+            .c(d!())
+    }
+
     /// Returns the set of records issued by a certain key.
     #[inline(always)]
     pub fn get_issued_records(
@@ -168,7 +213,7 @@ impl QueryServer {
     pub fn get_related_transactions(
         &self,
         address: &XfrAddress,
-    ) -> Option<HashSet<TxnSID>> {
+    ) -> Option<BTreeSet<TxnSID>> {
         self.ledger_cloned
             .api_cache
             .related_transactions
@@ -181,7 +226,7 @@ impl QueryServer {
     pub fn get_related_transfers(
         &self,
         code: &AssetTypeCode,
-    ) -> Option<HashSet<TxnSID>> {
+    ) -> Option<BTreeSet<TxnSID>> {
         self.ledger_cloned.api_cache.related_transfers.get(&code)
     }
 
@@ -241,3 +286,190 @@ impl QueryServer {
         }
     }
 }
+
+struct OwnershipChallenge {
+    pk: XfrPublicKey,
+    addr: String,
+    client: String,
+    expire_at: u64,
+}
+
+// Pending ownership challenges,
+// the requests are unauthenticated, so every kind of them is bounded,
+// and the oldest ones are evicted instead of refusing new requests.
+#[derive(Default)]
+struct OwnershipChallenges {
+    // nonce => challenge
+    pending: BTreeMap<String, OwnershipChallenge>,
+    // nonces in the order of issuing, which is also the order of expiring,
+    // the ones that have been taken are cleaned lazily
+    queue: VecDeque<String>,
+    // address => nonces in the order of issuing
+    by_addr: BTreeMap<String, VecDeque<String>>,
+    // client => nonces in the order of issuing
+    by_client: BTreeMap<String, VecDeque<String>>,
+}
+
+impl OwnershipChallenges {
+    fn issue(&mut self, pk: &XfrPublicKey, client: &str, now: u64) -> (String, u64) {
+        self.clean(now);
+
+        let addr = wallet::public_key_to_base64(pk);
+        if let Some(oldest) = self
+            .by_addr
+            .get(&addr)
+            .filter(|l| l.len() >= OWNERSHIP_CHALLENGE_MAX_PER_ADDR)
+            .and_then(|l| l.front().cloned())
+        {
+            self.remove(&oldest);
+        }
+        if let Some(oldest) = self
+            .by_client
+            .get(client)
+            .filter(|l| l.len() >= OWNERSHIP_CHALLENGE_MAX_PER_CLIENT)
+            .and_then(|l| l.front().cloned())
+        {
+            self.remove(&oldest);
+        }
+        while self.pending.len() >= OWNERSHIP_CHALLENGE_MAX {
+            if let Some(oldest) = self.queue.pop_front() {
+                self.remove(&oldest);
+            } else {
+                break;
+            }
+        }
+
+        let nonce = hex::encode(rand::random::<[u8; 32]>());
+        let expire_at = now + OWNERSHIP_CHALLENGE_TTL;
+
+        self.queue.push_back(nonce.clone());
+        self.by_addr
+            .entry(addr.clone())
+            .or_default()
+            .push_back(nonce.clone());
+        self.by_client
+            .entry(client.to_owned())
+            .or_default()
+            .push_back(nonce.clone());
+        self.pending.insert(
+            nonce.clone(),
+            OwnershipChallenge {
+                pk: *pk,
+                addr,
+                client: client.to_owned(),
+                expire_at,
+            },
+        );
+
+        (nonce, expire_at)
+    }
+
+    fn take(&mut self, nonce: &str, pk: &XfrPublicKey, now: u64) -> Result<()> {
+        let c = self.remove(nonce).c(d!("unknown nonce"))?;
+
+        if c.pk != *pk {
+            Err(eg!("the nonce was issued for another address"))
+        } else if c.expire_at <= now {
+            Err(eg!("the nonce has expired"))
+        } else {
+            Ok(())
+        }
+    }
+
+    // Drop the expired challenges,
+    // and the nonces that have been taken from the head of the queue.
+    fn clean(&mut self, now: u64) {
+        while let Some(nonce) = self.queue.front() {
+            match self.pending.get(nonce) {
+                Some(c) if c.expire_at > now => break,
+                Some(_) => {
+                    let nonce = nonce.clone();
+                    self.remove(&nonce);
+                }
+                None => {}
+            }
+            self.queue.pop_front();
+        }
+
+        // the taken nonces may pile up behind a pending one
+        if self.queue.len() > 2 * OWNERSHIP_CHALLENGE_MAX {
+            let pending = &self.pending;
+            self.queue.retain(|n| pending.contains_key(n));
+        }
+    }
+
+    fn remove(&mut self, nonce: &str) -> Option<OwnershipChallenge> {
+        let c = self.pending.remove(nonce)?;
+
+        for (idx, key) in [
+            (&mut self.by_addr, &c.addr),
+            (&mut self.by_client, &c.client),
+        ] {
+            if let Some(l) = idx.get_mut(key) {
+                l.retain(|n| n != nonce);
+                if l.is_empty() {
+                    idx.remove(key);
+                }
+            }
+        }
+
+        Some(c)
+    }
+}
+
+pub(crate) fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*, rand_chacha::ChaChaRng, rand_core::SeedableRng,
+        zei::xfr::sig::XfrKeyPair,
+    };
+
+    #[test]
+    fn ownership_challenges_bounded() {
+        let mut prng = ChaChaRng::from_entropy();
+        let pk = XfrKeyPair::generate(&mut prng).get_pk();
+        let mut ocs = OwnershipChallenges::default();
+
+        // the oldest challenge of an address is evicted
+        let nonces = (0..=OWNERSHIP_CHALLENGE_MAX_PER_ADDR)
+            .map(|i| ocs.issue(&pk, &format!("client-{}", i), 0).0)
+            .collect::<Vec<_>>();
+        assert_eq!(OWNERSHIP_CHALLENGE_MAX_PER_ADDR, ocs.pending.len());
+        assert!(ocs.take(&nonces[0], &pk, 0).is_err());
+        pnk!(ocs.take(&nonces[1], &pk, 0));
+        assert!(ocs.take(&nonces[1], &pk, 0).is_err());
+
+        // the oldest challenge of a client is evicted
+        let mut ocs = OwnershipChallenges::default();
+        let kps = (0..=OWNERSHIP_CHALLENGE_MAX_PER_CLIENT)
+            .map(|_| XfrKeyPair::generate(&mut prng))
+            .collect::<Vec<XfrKeyPair>>();
+        let nonces = kps
+            .iter()
+            .map(|kp| ocs.issue(kp.get_pk_ref(), "client", 0).0)
+            .collect::<Vec<_>>();
+        assert_eq!(OWNERSHIP_CHALLENGE_MAX_PER_CLIENT, ocs.pending.len());
+        assert!(ocs.take(&nonces[0], kps[0].get_pk_ref(), 0).is_err());
+        pnk!(ocs.take(&nonces[1], kps[1].get_pk_ref(), 0));
+
+        // a nonce is bound to its address and expires
+        let (nonce, expire_at) = ocs.issue(&pk, "client", 0);
+        assert!(ocs.take(&nonce, kps[2].get_pk_ref(), 0).is_err());
+        let (nonce, expire_at_2) = ocs.issue(&pk, "client", 0);
+        assert_eq!(expire_at, expire_at_2);
+        assert!(ocs.take(&nonce, &pk, expire_at).is_err());
+
+        // expired challenges are dropped
+        ocs.issue(&pk, "client", expire_at);
+        assert_eq!(1, ocs.pending.len());
+        assert_eq!(1, ocs.by_addr.len());
+        assert_eq!(1, ocs.by_client.len());
+    }
+}
@@ -13,7 +13,10 @@ use {
     rand_core::{CryptoRng, RngCore},
     ruc::*,
     serde::{Deserialize, Serialize},
-    std::{collections::HashMap, fmt, result::Result as StdResult, sync::Arc},
+    std::{
+        collections::HashMap, fmt, mem, net::IpAddr, result::Result as StdResult,
+        sync::Arc,
+    },
 };
 
 /// Query handle for user
@@ -52,12 +55,34 @@ pub enum CommitMode {
     Manual, // Somebody else calls commit. Not this code.
 }
 
+/// How many blocks a txn of a failed block is kept as pending,
+/// it is marked as rejected if no finalized block contains it by then.
+pub const PENDING_KEEP_ALIVE_BLOCKS: usize = 8;
+
+/// HTTP header carrying how many stem peers a relayed transaction has passed
+pub const STEM_HOPS_HEADER: &str = "X-Findora-Stem-Hops";
+
+/// A relayed transaction will be broadcasted once it reaches this many hops,
+/// and relays claiming more hops than this are dropped
+pub const MAX_STEM_HOPS: u8 = 4;
+
 /// Define txforward trait,
 /// the impl of different functions is different, and the specific impl is in
 /// `src/components/abciapp/server/tx_sender.rs`
 #[allow(missing_docs)]
 pub trait TxnForward: AsRef<str> {
     fn forward_txn(&self, txn: Transaction) -> Result<()>;
+
+    /// forward a transaction relayed from a stem peer,
+    /// `hops` is the number of stem peers it has passed
+    fn forward_relayed_txn(&self, txn: Transaction, _hops: u8) -> Result<()> {
+        self.forward_txn(txn)
+    }
+
+    /// whether the hop count of relays from this address can be trusted
+    fn is_stem_peer(&self, _ip: &IpAddr) -> bool {
+        false
+    }
 }
 
 /// Define SubmissionServer
@@ -69,6 +94,10 @@ where
     committed_state: Arc<RwLock<LedgerState>>,
     block: Option<BlockEffect>,
     pending_txns: Vec<(TxnTempSID, TxnHandle, Transaction)>,
+    // txns of failed blocks, with the block count at which they expire
+    unresolved_txns: Vec<(TxnHandle, usize)>,
+    // number of finalized blocks whose txns have been seen by `recover_txn_status`
+    recovered_blocks: usize,
     txn_status: HashMap<TxnHandle, TxnStatus>,
     block_capacity: usize,
     prng: RNG,
@@ -88,16 +117,21 @@ where
         block_capacity: usize,
         txn_forwarder: TF,
     ) -> Result<SubmissionServer<RNG, TF>> {
-        Ok(SubmissionServer {
+        let recovered_blocks = ledger_state.read().get_block_count().saturating_sub(1);
+        let mut server = SubmissionServer {
             committed_state: ledger_state,
             block: None,
             txn_status: HashMap::new(),
             pending_txns: vec![],
+            unresolved_txns: vec![],
+            recovered_blocks,
             prng,
             block_capacity,
             commit_mode: CommitMode::FullBlock,
             txn_forwarder,
-        })
+        };
+        server.recover_txn_status();
+        Ok(server)
     }
 
     /// Create to no auto commit
@@ -106,16 +140,21 @@ where
         ledger_state: Arc<RwLock<LedgerState>>,
         txn_forwarder: TF,
     ) -> Result<SubmissionServer<RNG, TF>> {
-        Ok(SubmissionServer {
+        let recovered_blocks = ledger_state.read().get_block_count().saturating_sub(1);
+        let mut server = SubmissionServer {
             committed_state: ledger_state,
             block: None,
             txn_status: HashMap::new(),
             pending_txns: vec![],
+            unresolved_txns: vec![],
+            recovered_blocks,
             prng,
             block_capacity: 0,
             commit_mode: CommitMode::Manual,
             txn_forwarder,
-        })
+        };
+        server.recover_txn_status();
+        Ok(server)
     }
 
     /// Query operation results
@@ -180,23 +219,98 @@ where
     /// it is the logic to write the block to the ledgerState
     pub fn end_block(&mut self) -> Result<()> {
         if let Some(block) = self.block.take() {
+            // `TxnTempSID`s are only meaningful within the block just taken,
+            // so the pending txns must never be carried over to the next one
+            let pending_txns = mem::take(&mut self.pending_txns);
+
             let mut ledger = self.committed_state.write();
-            let finalized_txns = ledger.finish_block(block).c(d!())?;
+            let finished = ledger.finish_block(block).c(d!());
+            let expire_at = ledger.get_block_count() + PENDING_KEEP_ALIVE_BLOCKS;
+            drop(ledger);
+
+            let finalized_txns = match finished {
+                Ok(tsm) => tsm,
+                Err(e) => {
+                    // the effects of the block may have been applied before the failure,
+                    // so keep its txns pending until a finalized block tells the truth
+                    self.unresolved_txns.extend(
+                        pending_txns
+                            .into_iter()
+                            .map(|(_, handle, _)| (handle, expire_at)),
+                    );
+                    self.recover_txn_status();
+                    return Err(e);
+                }
+            };
 
             // Update status of all committed transactions
-            for (txn_temp_sid, handle, _txn) in self.pending_txns.drain(..) {
-                let committed_txn_info = finalized_txns.get(&txn_temp_sid).c(d!())?;
-                self.txn_status
-                    .insert(handle, TxnStatus::Committed(committed_txn_info.clone()));
+            let mut missing = 0;
+            for (txn_temp_sid, handle, _txn) in pending_txns {
+                let status = if let Some(info) = finalized_txns.get(&txn_temp_sid) {
+                    TxnStatus::Committed(info.clone())
+                } else {
+                    missing += 1;
+                    TxnStatus::Rejected(format!(
+                        "{:?} not found in the finished block",
+                        txn_temp_sid
+                    ))
+                };
+                self.txn_status.insert(handle, status);
+            }
+
+            self.recover_txn_status();
+
+            if 0 < missing {
+                return Err(eg!(format!(
+                    "{} pending txns not found in the finished block",
+                    missing
+                )));
             }
 
-            self.pending_txns = Vec::new();
             return Ok(());
         }
 
         Err(eg!("Cannot finish block because there are no pending txns"))
     }
 
+    /// Rebuild the status of txns from the blocks finalized since the last call,
+    /// or from the last finalized block after a restart,
+    /// txns of failed blocks are resolved by them or rejected once expired.
+    pub fn recover_txn_status(&mut self) {
+        let ledger = self.committed_state.read();
+        let block_cnt = ledger.get_block_count();
+
+        for idx in self.recovered_blocks..block_cnt {
+            if let Some(b) = ledger.blocks.get(idx) {
+                for ft in b.txns.iter() {
+                    self.txn_status.insert(
+                        TxnHandle::new(&ft.txn),
+                        TxnStatus::Committed((ft.tx_id, ft.txo_ids.clone())),
+                    );
+                }
+            }
+        }
+        self.recovered_blocks = block_cnt;
+
+        let txn_status = &mut self.txn_status;
+        self.unresolved_txns.retain(|(handle, expire_at)| {
+            if let Some(TxnStatus::Committed(_)) = txn_status.get(handle) {
+                false
+            } else if *expire_at <= block_cnt {
+                txn_status.insert(
+                    handle.clone(),
+                    TxnStatus::Rejected(
+                        "not found in any block finalized after its block failed"
+                            .to_owned(),
+                    ),
+                );
+                false
+            } else {
+                true
+            }
+        });
+    }
+
     /// Get txs number of pending
     pub fn block_txn_count(&self) -> usize {
         self.pending_txns.len()
@@ -246,6 +360,23 @@ where
         Ok(txn_handle)
     }
 
+    /// Handle a transaction relayed from a stem peer
+    pub fn handle_relayed_transaction(
+        &mut self,
+        txn: Transaction,
+        hops: u8,
+    ) -> Result<TxnHandle> {
+        if hops > MAX_STEM_HOPS {
+            return Err(eg!(format!(
+                "too many stem hops: {} > {}",
+                hops, MAX_STEM_HOPS
+            )));
+        }
+        let txn_handle = TxnHandle::new(&txn);
+        self.txn_forwarder.forward_relayed_txn(txn, hops).c(d!())?;
+        Ok(txn_handle)
+    }
+
     #[allow(missing_docs)]
     pub fn get_fwder(&self) -> &TF {
         &self.txn_forwarder
@@ -257,3 +388,129 @@ where
 pub fn convert_tx(tx: &[u8]) -> Result<Transaction> {
     serde_json::from_slice(tx).c(d!())
 }
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*, ledger::store::utils::fra_gen_initial_tx, rand_chacha::ChaChaRng,
+        rand_core::SeedableRng, zei::xfr::sig::XfrKeyPair,
+    };
+
+    struct NopForward;
+
+    impl AsRef<str> for NopForward {
+        fn as_ref(&self) -> &str {
+            ""
+        }
+    }
+
+    impl TxnForward for NopForward {
+        fn forward_txn(&self, _txn: Transaction) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn end_block_drains_unresolved_pending_txns() {
+        let ledger = Arc::new(RwLock::new(LedgerState::tmp_ledger()));
+        let mut server = pnk!(SubmissionServer::new_no_auto_commit(
+            ChaChaRng::from_entropy(),
+            ledger,
+            NopForward,
+        ));
+
+        let txn = Transaction::from_seq_id(0);
+        let handle = TxnHandle::new(&txn);
+
+        server.begin_block();
+        // no such txn in the block
+        server
+            .pending_txns
+            .push((TxnTempSID(0), handle.clone(), txn));
+        server.txn_status.insert(handle.clone(), TxnStatus::Pending);
+
+        assert!(server.end_block().is_err());
+        assert!(server.all_commited());
+        assert_eq!(0, server.block_txn_count());
+        assert!(matches!(
+            server.get_txn_status(&handle),
+            Some(TxnStatus::Rejected(_))
+        ));
+
+        // the next block starts clean
+        server.begin_block();
+        pnk!(server.end_block());
+    }
+
+    #[test]
+    fn txn_status_is_recovered_from_finalized_blocks() {
+        let ledger = Arc::new(RwLock::new(LedgerState::tmp_ledger()));
+        let mut server = pnk!(SubmissionServer::new_no_auto_commit(
+            ChaChaRng::from_entropy(),
+            ledger.clone(),
+            NopForward,
+        ));
+
+        let txn =
+            fra_gen_initial_tx(&XfrKeyPair::generate(&mut ChaChaRng::from_entropy()));
+        let handle = TxnHandle::new(&txn);
+        let lost = TxnHandle::new(&Transaction::from_seq_id(0));
+
+        // txns of a failed block
+        server.unresolved_txns.push((handle.clone(), 2));
+        server.unresolved_txns.push((lost.clone(), 2));
+
+        // the block is finalized by the ledger without the submission server
+        {
+            let mut l = ledger.write();
+            let effect = pnk!(TxnEffect::compute_effect(txn));
+            let mut block = pnk!(l.start_block());
+            pnk!(l.apply_transaction(&mut block, effect));
+            pnk!(l.finish_block(block));
+        }
+
+        server.recover_txn_status();
+        assert!(matches!(
+            server.get_txn_status(&handle),
+            Some(TxnStatus::Committed(_))
+        ));
+        // still waiting for a finalized block
+        assert_eq!(server.unresolved_txns.len(), 1);
+        assert!(server.get_txn_status(&lost).is_none());
+
+        // a restarted server knows the txns of the last finalized block
+        let restarted = pnk!(SubmissionServer::new_no_auto_commit(
+            ChaChaRng::from_entropy(),
+            ledger,
+            NopForward,
+        ));
+        assert!(matches!(
+            restarted.get_txn_status(&handle),
+            Some(TxnStatus::Committed(_))
+        ));
+
+        server.begin_block();
+        pnk!(server.end_block());
+        assert!(server.unresolved_txns.is_empty());
+        assert!(matches!(
+            server.get_txn_status(&lost),
+            Some(TxnStatus::Rejected(_))
+        ));
+    }
+
+    #[test]
+    fn relays_past_max_stem_hops_are_dropped() {
+        let ledger = Arc::new(RwLock::new(LedgerState::tmp_ledger()));
+        let mut server = pnk!(SubmissionServer::new_no_auto_commit(
+            ChaChaRng::from_entropy(),
+            ledger,
+            NopForward,
+        ));
+
+        let txn = Transaction::from_seq_id(0);
+        pnk!(server.handle_relayed_transaction(txn.clone(), MAX_STEM_HOPS));
+        assert!(server
+            .handle_relayed_transaction(txn, MAX_STEM_HOPS + 1)
+            .is_err());
+    }
+}
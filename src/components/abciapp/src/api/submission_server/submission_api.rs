@@ -3,9 +3,9 @@
 //!
 
 use {
-    super::{SubmissionServer, TxnForward, TxnHandle},
+    super::{SubmissionServer, TxnForward, TxnHandle, STEM_HOPS_HEADER},
     actix_cors::Cors,
-    actix_web::{error, middleware, web, App, HttpServer},
+    actix_web::{error, middleware, web, App, HttpRequest, HttpServer},
     finutils::api::NetworkRoute,
     ledger::data_model::Transaction,
     log::info,
@@ -34,6 +34,7 @@ async fn version() -> actix_web::Result<String> {
 
 /// Sending transactions to tendermint
 pub async fn submit_transaction<RNG, TF>(
+    req: HttpRequest,
     data: web::Data<Arc<RwLock<SubmissionServer<RNG, TF>>>>,
     body: web::Json<Transaction>,
 ) -> StdResult<web::Json<TxnHandle>, actix_web::error::Error>
@@ -44,13 +45,52 @@ where
     let tx = body.into_inner();
 
     let mut submission_server = data.write();
-    submission_server
-        .handle_transaction(tx)
-        .map(web::Json)
-        .map_err(|e| {
-            e.print(None);
-            error::ErrorBadRequest(e.generate_log(None))
-        })
+
+    // the hop count is only trusted on relays from the configured stem peers,
+    // other submissions always start a new stem phase
+    let from_stem_peer = req
+        .peer_addr()
+        .map(|addr| submission_server.get_fwder().is_stem_peer(&addr.ip()))
+        .unwrap_or(false);
+    let hops = match req.headers().get(STEM_HOPS_HEADER) {
+        Some(h) if from_stem_peer => Some(
+            h.to_str()
+                .ok()
+                .and_then(|h| h.parse::<u8>().ok())
+                .ok_or_else(|| error::ErrorBadRequest("invalid stem hops"))?,
+        ),
+        _ => None,
+    };
+
+    let res = if let Some(hops) = hops {
+        submission_server.handle_relayed_transaction(tx, hops)
+    } else {
+        submission_server.handle_transaction(tx)
+    };
+
+    res.map(web::Json).map_err(|e| {
+        e.print(None);
+        error::ErrorBadRequest(e.generate_log(None))
+    })
+}
+
+/// Check a transaction against current state without submitting it
+pub async fn simulate_transaction<RNG, TF>(
+    data: web::Data<Arc<RwLock<SubmissionServer<RNG, TF>>>>,
+    body: web::Json<Transaction>,
+) -> StdResult<String, actix_web::error::Error>
+where
+    RNG: RngCore + CryptoRng,
+    TF: TxnForward + Sync + Send,
+{
+    let tx = body.into_inner();
+
+    let submission_server = data.read();
+    let ledger = submission_server.get_committed_state().read();
+    ledger
+        .simulate_transaction(tx)
+        .map(|_| "success".to_owned())
+        .map_err(|e| error::ErrorBadRequest(e.generate_log(None)))
 }
 
 /// Queries the status of a transaction by its handle. Returns either a not committed message or a
@@ -85,6 +125,7 @@ pub struct SubmissionApi;
 #[allow(missing_docs)]
 pub enum SubmissionRoutes {
     SubmitTransaction,
+    SimulateTransaction,
     TxnStatus,
     Ping,
     Version,
@@ -94,6 +135,7 @@ impl NetworkRoute for SubmissionRoutes {
     fn route(&self) -> String {
         let endpoint = match *self {
             SubmissionRoutes::SubmitTransaction => "submit_transaction",
+            SubmissionRoutes::SimulateTransaction => "simulate_transaction",
             SubmissionRoutes::TxnStatus => "txn_status",
             SubmissionRoutes::Ping => "ping",
             SubmissionRoutes::Version => "version",
@@ -124,6 +166,10 @@ impl SubmissionApi {
                     &SubmissionRoutes::SubmitTransaction.route(),
                     web::post().to(submit_transaction::<RNG, TF>),
                 )
+                .route(
+                    &SubmissionRoutes::SimulateTransaction.route(),
+                    web::post().to(simulate_transaction::<RNG, TF>),
+                )
                 .route(&SubmissionRoutes::Ping.route(), web::get().to(ping))
                 .route(&SubmissionRoutes::Version.route(), web::get().to(version))
                 .route(
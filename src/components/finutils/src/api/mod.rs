@@ -7,6 +7,7 @@ use {
         self, StakerMemo, TendermintAddr, MAX_POWER_PERCENT_PER_VALIDATOR,
     },
     serde::{Deserialize, Serialize},
+    std::collections::BTreeMap,
 };
 
 /// A list of basic validator information of current height
@@ -97,6 +98,27 @@ pub struct ValidatorDetail {
     pub delegator_cnt: u64,
 }
 
+/// Earnings of a validator, assembled at the server side for dashboards.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ValidatorEarnings {
+    /// tendermint node address
+    pub addr: TendermintAddr,
+    /// cumulative commissions paid by its delegators
+    pub commission_total: u64,
+    /// cumulative rewards of its self-delegation
+    pub self_delegation_rwd_total: u64,
+    /// cumulative rewards of proposing blocks
+    pub proposer_rwd_total: u64,
+    /// current voting power
+    pub voting_power: u64,
+    /// current voting power / total voting power
+    pub voting_power_share: [u64; 2],
+    /// total number of its delegators
+    pub delegator_cnt: u64,
+    /// current block height
+    pub cur_height: u64,
+}
+
 #[allow(missing_docs)]
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub struct DelegatorInfo {
@@ -185,6 +207,148 @@ impl DelegationInfo {
     }
 }
 
+/// Where the value of a chain parameter comes from.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParamSource {
+    /// hard-coded, can only be changed by an upgrade
+    CompileTime,
+    /// can be changed by on-chain governance
+    Governance,
+    /// derived from the current on-chain state
+    Dynamic,
+    /// configured by the queried node itself, not a part of the consensus
+    NodeLocal,
+}
+
+/// A chain parameter together with its source.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ChainParam<T> {
+    #[allow(missing_docs)]
+    pub value: T,
+    #[allow(missing_docs)]
+    pub source: ParamSource,
+}
+
+impl<T> ChainParam<T> {
+    #[inline(always)]
+    #[allow(missing_docs)]
+    pub fn new(value: T, source: ParamSource) -> Self {
+        ChainParam { value, source }
+    }
+}
+
+/// All consensus-relevant parameters that are active at current height,
+/// wallets and explorers should use these instead of hard-coding them.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ChainParams {
+    /// current block height
+    pub cur_height: u64,
+    /// minimal fee of a transaction, in FRA units
+    pub tx_fee_min: ChainParam<u64>,
+    /// decimals of FRA
+    pub fra_decimals: ChainParam<u8>,
+    /// total amount of FRA issuance, in FRA units
+    pub fra_pre_issue_amount: ChainParam<u64>,
+    /// issuance + token pool of CoinBase, in FRA units
+    pub fra_total_amount: ChainParam<u64>,
+    /// block time interval, in seconds
+    pub block_interval: ChainParam<u64>,
+    /// lock time after a delegation expires, in blocks
+    pub unbond_block_cnt: ChainParam<u64>,
+    /// minimum allowable delegation amount
+    pub min_delegation_amount: ChainParam<u64>,
+    /// maximum allowable delegation amount
+    pub max_delegation_amount: ChainParam<u64>,
+    /// minimum investment to become a validator
+    pub staking_validator_min_power: ChainParam<u64>,
+    /// max vote power percent of any validator
+    pub max_power_percent_per_validator: ChainParam<[u128; 2]>,
+    /// max global power of the validator set
+    pub max_total_power: ChainParam<u64>,
+    /// validator set is updated every N blocks
+    pub validator_update_block_itv: ChainParam<i64>,
+    /// `([low vote percent, high vote percent], rate%)` of proposer rewards
+    pub proposer_rewards_rate_rule: ChainParam<Vec<([u128; 2], u128)>>,
+    /// current return rate of delegations
+    pub block_rewards_rate: ChainParam<[u128; 2]>,
+    /// global delegations / global issuances
+    pub global_delegation_percent: ChainParam<[u64; 2]>,
+    /// weight threshold of co-signatures when updating validators
+    pub cosig_threshold: ChainParam<[u64; 2]>,
+    /// max size of a block in bytes, `None` means the genesis one of tendermint
+    pub block_max_bytes: ChainParam<Option<i64>>,
+    /// max gas of a block, `None` means the genesis one of tendermint
+    pub block_max_gas: ChainParam<Option<i64>>,
+    /// assets that can be used to pay fees, `asset code => [x, y]`,
+    /// `x` units of the asset are worth `y` units of FRA,
+    /// a fee paid in them is burned, not converted into FRA
+    pub fee_assets: ChainParam<BTreeMap<String, [u64; 2]>>,
+    /// minimal fee accepted by the mempool of the queried node,
+    /// in base units of FRA, 1 FRA is `10^FRA_DECIMALS` units
+    pub check_tx_min_fee: ChainParam<u64>,
+    /// max commission rate of a validator
+    pub commission_rate_max: ChainParam<[u64; 2]>,
+    /// max change of the commission rate in a single update
+    pub commission_rate_max_change: ChainParam<[u64; 2]>,
+    /// the commission rate can be updated at most once in N blocks
+    pub commission_rate_update_block_itv: ChainParam<u64>,
+    /// the limits of commission rate updates take effect from this height
+    pub commission_rate_update_check_height: ChainParam<u64>,
+}
+
+/// Ask a node for an `OwnershipChallenge`.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct OwnershipChallengeRequest {
+    /// `XfrPublicKey` in base64 format
+    pub address: String,
+}
+
+/// A one-time challenge issued by a node,
+/// sign its `message` to prove the ownership of `address`.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct OwnershipChallenge {
+    /// `XfrPublicKey` in base64 format
+    pub address: String,
+    #[allow(missing_docs)]
+    pub nonce: String,
+    /// the message to sign, SEE `globutils::wallet::ownership_challenge_message`
+    pub message: String,
+    /// unix timestamp in seconds, the nonce is invalid after it
+    pub expire_at: u64,
+}
+
+/// The answer of a wallet to an `OwnershipChallenge`.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct OwnershipProof {
+    /// `XfrPublicKey` in base64 format
+    pub address: String,
+    #[allow(missing_docs)]
+    pub nonce: String,
+    /// `XfrSignature` in base64 format
+    pub signature: String,
+}
+
+/// Returned by a node after an `OwnershipProof` has been verified.
+///
+/// It is NOT signed by the node,
+/// so it proves nothing to anyone other than the one who has requested it.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct OwnershipAttestation {
+    /// `XfrPublicKey` in base64 format
+    pub address: String,
+    #[allow(missing_docs)]
+    pub nonce: String,
+    /// the height of the ledger state used to verify the proof
+    pub block_height: u64,
+    /// unix timestamp in seconds
+    pub verified_at: u64,
+    /// number of utxos owned by the address
+    pub owned_utxo_cnt: u64,
+    /// non-confidential FRA owned by the address, in FRA units
+    pub fra_balance: u64,
+}
+
 #[allow(missing_docs)]
 pub trait NetworkRoute {
     fn route(&self) -> String;
@@ -1,4 +1,9 @@
-use {globutils::wallet, ledger::staking::init, ruc::*, std::fs};
+use {
+    globutils::wallet,
+    ledger::staking::init,
+    ruc::*,
+    std::{env, fs},
+};
 
 fn main() {
     pnk!(gen());
@@ -29,6 +34,27 @@ fn gen() -> Result<()> {
             v.id = wallet::public_key_to_base64(m.1.get_pk_ref());
         });
 
+    // the validator-set has been changed,
+    // so it must be signed by the bootstrap keys again,
+    // their mnemonics are separated by ','
+    cfg_template.signatures.clear();
+    if let Ok(mnemonics) = env::var("STAKING_GENESIS_BOOTSTRAP_MNEMONICS") {
+        for m in mnemonics
+            .split(',')
+            .map(|m| m.trim())
+            .filter(|m| !m.is_empty())
+        {
+            wallet::restore_keypair_from_mnemonic_default(m)
+                .c(d!())
+                .and_then(|kp| cfg_template.sign(&kp).c(d!()))?;
+        }
+    }
+    if cfg_template.signatures.is_empty() {
+        println!(
+            "\x1b[31;01mWARNING: the config is not signed by any bootstrap key\x1b[00m"
+        );
+    }
+
     let cfg = cfg_template;
     serde_json::to_vec_pretty(&cfg)
         .c(d!())
@@ -73,6 +73,8 @@ fn run() -> Result<()> {
                 None
             };
             common::show_account(seckey.as_deref(), asset).c(d!())?;
+        } else if let Some(path) = m.value_of("encrypt") {
+            common::encrypt_key_file(path).c(d!())?;
         } else {
             println!("{}", m.usage());
         }
@@ -5,6 +5,7 @@
 //! - delegate --user=<cat1> --amount=<N> --validator=<dog1>
 //! - undelegate --user=<cat1>
 //! - claim --user=<cat1> --amount=<N>
+//! - reset --height=<H> --snapshot-target=<zfs/data>
 //!
 
 #![deny(warnings)]
@@ -84,6 +85,10 @@ fn run() -> Result<()> {
         .arg_from_usage("-U, --user-list 'show the pre-defined user list'")
         .arg_from_usage("-v, --validator-list 'show the pre-defined validator list'")
         .arg_from_usage("-u, --user=[User] 'user name of delegator'");
+    let subcmd_reset = SubCommand::with_name("reset")
+        .about("rollback a local devnet to a retained snapshot, all nodes must be stopped first")
+        .arg_from_usage("-H, --height=[Height] 'the snapshot height to rollback to'")
+        .arg_from_usage("-t, --snapshot-target=[TargetPath] 'a data volume containing both ledger data and tendermint data'");
 
     let matches = App::new("stt")
         .version(common::version())
@@ -98,6 +103,7 @@ fn run() -> Result<()> {
         .subcommand(subcmd_claim)
         .subcommand(subcmd_transfer)
         .subcommand(subcmd_show)
+        .subcommand(subcmd_reset)
         .get_matches();
 
     if matches.is_present("version") {
@@ -189,6 +195,19 @@ fn run() -> Result<()> {
         } else {
             println!("{}", m.usage());
         }
+    } else if let Some(m) = matches.subcommand_matches("reset") {
+        let height = m.value_of("height");
+        let target = m
+            .value_of("snapshot-target")
+            .map(|t| t.to_owned())
+            .or_else(|| env::var("SNAPSHOT_TARGET").ok());
+
+        if let (Some(h), Some(t)) = (height, target) {
+            let h = h.parse::<u64>().c(d!())?;
+            reset::reset(h, &t).c(d!())?;
+        } else {
+            println!("{}", m.usage());
+        }
     } else {
         println!("{}", matches.usage());
     }
@@ -196,6 +215,36 @@ fn run() -> Result<()> {
     Ok(())
 }
 
+mod reset {
+    use {super::*, std::process::Command};
+
+    /// Rollback the ledger and tendermint data to a snapshot at exactly `h`,
+    /// this relies on the snapshot functions of `abcid`.
+    pub fn reset(h: u64, target: &str) -> Result<()> {
+        if !cfg!(any(feature = "debug_env", feature = "abci_mock")) {
+            return Err(eg!("`reset` is only available in dev builds"));
+        }
+
+        let status = Command::new("abcid")
+            .arg("--snapshot-target")
+            .arg(target)
+            .arg("--snapshot-rollback-to-exact")
+            .arg(h.to_string())
+            .status()
+            .c(d!("fail to run `abcid`"))?;
+
+        if status.success() {
+            println!("The devnet has been reset to height {}", h);
+            Ok(())
+        } else {
+            Err(eg!(format!(
+                "rollback failed, is the snapshot at {} retained?",
+                h
+            )))
+        }
+    }
+}
+
 mod issue {
     use {
         super::*,
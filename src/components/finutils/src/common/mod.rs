@@ -9,7 +9,7 @@
 pub mod utils;
 
 use {
-    crate::api::DelegationInfo,
+    crate::{api::DelegationInfo, keystore},
     globutils::wallet,
     lazy_static::lazy_static,
     ledger::{
@@ -53,6 +53,8 @@ lazy_static! {
     static ref SERV_ADDR_FILE: String = format!("{}/serv_addr", &*CFG_PATH);
 }
 
+const KEYSTORE_PASSWORD_VAR: &str = "FN_KEYSTORE_PASSWORD";
+
 /// Updating the information of a staker includes commission_rate and staker_memo
 pub fn staker_update(cr: Option<&str>, memo: Option<&str>) -> Result<()> {
     let addr = get_td_pubkey().map(|i| td_pubkey_to_td_addr(&i)).c(d!())?;
@@ -477,11 +479,21 @@ pub fn get_serv_addr() -> Result<&'static str> {
     }
 }
 
-/// Get keypair from config file
+/// Get keypair from config file,
+/// the file can be encrypted by `encrypt_key_file`.
 pub fn get_keypair() -> Result<XfrKeyPair> {
     if let Some(m_path) = MNEMONIC.as_ref() {
-        fs::read_to_string(m_path)
+        fs::read(m_path)
             .c(d!("can not read mnemonic from 'owner-mnemonic-path'"))
+            .and_then(|m| {
+                if keystore::is_encrypted(&m) {
+                    keystore::decrypt(&m, get_keystore_password().c(d!())?.as_bytes())
+                        .c(d!())
+                } else {
+                    Ok(m)
+                }
+            })
+            .and_then(|m| String::from_utf8(m).c(d!()))
             .and_then(|m| {
                 let k = m.trim();
                 wallet::restore_keypair_from_mnemonic_default(k)
@@ -493,6 +505,36 @@ pub fn get_keypair() -> Result<XfrKeyPair> {
     }
 }
 
+// The password of encrypted key files.
+fn get_keystore_password() -> Result<String> {
+    env::var(KEYSTORE_PASSWORD_VAR).c(d!(format!(
+        "the key file is encrypted, please set the password in '{}'",
+        KEYSTORE_PASSWORD_VAR
+    )))
+}
+
+/// Encrypt a file of mnemonic words or secret key,
+/// the result is written to `<path>.enc`,
+/// and the password is read from `FN_KEYSTORE_PASSWORD`.
+pub fn encrypt_key_file(path: &str) -> Result<()> {
+    let data = fs::read(path).c(d!("can not read the key file"))?;
+    if keystore::is_encrypted(&data) {
+        return Err(eg!("the key file has been encrypted"));
+    }
+
+    let enc =
+        keystore::encrypt(&data, get_keystore_password().c(d!())?.as_bytes()).c(d!())?;
+    let enc_path = format!("{}.enc", path);
+    fs::write(&enc_path, enc).c(d!())?;
+
+    println!(
+        "Encrypted key file: {}, please remove the original one after checking it.",
+        enc_path
+    );
+
+    Ok(())
+}
+
 fn get_td_pubkey() -> Result<Vec<u8>> {
     if let Some(key_path) = TD_KEY.as_ref() {
         fs::read_to_string(key_path)
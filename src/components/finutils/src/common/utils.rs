@@ -11,9 +11,9 @@ use {
     globutils::{wallet, HashOf, SignatureOf},
     ledger::{
         data_model::{
-            AssetType, AssetTypeCode, DefineAsset, Operation, StateCommitmentData,
-            Transaction, TransferType, TxoRef, TxoSID, Utxo, ASSET_TYPE_FRA,
-            BLACK_HOLE_PUBKEY, TX_FEE_MIN,
+            AssetType, AssetTypeCode, DefineAsset, FinalizedTransaction, Operation,
+            StateCommitmentData, Transaction, TransferType, TxoRef, TxoSID, Utxo,
+            ASSET_TYPE_FRA, BLACK_HOLE_PUBKEY, TX_FEE_MIN,
         },
         staking::{init::get_inital_validators, TendermintAddrRef, FRA_TOTAL_AMOUNT},
     },
@@ -52,6 +52,21 @@ pub fn send_tx(tx: &Transaction) -> Result<()> {
         .map(|_| ())
 }
 
+/// Check a transaction against the current state of the server,
+/// it will not be submitted.
+#[inline(always)]
+pub fn simulate_tx(tx: &Transaction) -> Result<()> {
+    let url = format!("{}:8669/simulate_transaction", get_serv_addr().c(d!())?);
+    attohttpc::post(&url)
+        .header(attohttpc::header::CONTENT_TYPE, "application/json")
+        .bytes(&serde_json::to_vec(tx).c(d!())?)
+        .send()
+        .c(d!("fail to send transaction"))?
+        .error_for_status()
+        .c(d!())
+        .map(|_| ())
+}
+
 /// Fee is needless in a `UpdateValidator` operation
 #[inline(always)]
 pub fn set_initial_validators() -> Result<()> {
@@ -493,6 +508,20 @@ pub fn get_owner_memo_batch(ids: &[TxoSID]) -> Result<Vec<Option<OwnerMemo>>> {
         .and_then(|b| serde_json::from_slice(&b).c(d!()))
 }
 
+/// Get a finalized transaction by its tendermint hash.
+pub fn get_txn_by_hash(hash: &str) -> Result<FinalizedTransaction> {
+    let url = format!("{}:8668/txn_by_hash/{}", get_serv_addr().c(d!())?, hash);
+
+    attohttpc::get(&url)
+        .send()
+        .c(d!())?
+        .error_for_status()
+        .c(d!())?
+        .bytes()
+        .c(d!())
+        .and_then(|b| serde_json::from_slice::<FinalizedTransaction>(&b).c(d!()))
+}
+
 /// Delegation info(and staking info if `pk` is a validator).
 pub fn get_delegation_info(pk: &XfrPublicKey) -> Result<DelegationInfo> {
     let url = format!(
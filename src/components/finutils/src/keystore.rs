@@ -0,0 +1,145 @@
+//!
+//! # Key Store
+//!
+//! An encrypted container for secret keys or mnemonics,
+//! shared by the wasm/mobile bindings and the `fn` cli.
+//!
+//! The encryption key is derived from a password by Argon2id,
+//! and the content is encrypted by AES-256-GCM.
+//!
+//! Layout of the container:
+//!
+//! `MAGIC(4) | VERSION(1) | m_cost(4) | t_cost(4) | p_cost(4) | salt(16) | iv(12) | ciphertext`
+//!
+
+use {
+    aes_gcm::{
+        aead::{generic_array::GenericArray, Aead, NewAead},
+        Aes256Gcm,
+    },
+    rand::{thread_rng, Rng},
+    ruc::*,
+};
+
+const MAGIC: &[u8; 4] = b"FRAW";
+const VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+const IV_LEN: usize = 12;
+const HEADER_LEN: usize = 4 + 1 + 3 * 4 + SALT_LEN + IV_LEN;
+
+// KDF params of new containers, the memory cost is in KiB.
+const M_COST: u32 = 19456;
+const T_COST: u32 = 2;
+const P_COST: u32 = 1;
+
+// The params of a container are untrusted input,
+// anything above these limits is rejected before running the KDF.
+const M_COST_MAX: u32 = 256 * 1024;
+const T_COST_MAX: u32 = 16;
+const P_COST_MAX: u32 = 16;
+
+fn derive_key(
+    password: &[u8],
+    salt: &[u8],
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+) -> Result<[u8; 32]> {
+    if m_cost > M_COST_MAX || t_cost > T_COST_MAX || p_cost > P_COST_MAX {
+        return Err(eg!("KDF params out of range"));
+    }
+
+    let params =
+        argon2::Params::new(m_cost, t_cost, p_cost, Some(32)).map_err(|e| eg!(e))?;
+    let mut derived_key = [0u8; 32];
+    argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params)
+        .hash_password_into(password, salt, &mut derived_key)
+        .map_err(|e| eg!(e))?;
+    Ok(derived_key)
+}
+
+/// Check if the data looks like a container of this module.
+#[inline(always)]
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.len() > HEADER_LEN && data[..4] == MAGIC[..]
+}
+
+/// Encrypt the data into a versioned container.
+pub fn encrypt(data: &[u8], password: &[u8]) -> Result<Vec<u8>> {
+    let mut rng = thread_rng();
+
+    let mut salt = [0u8; SALT_LEN];
+    rng.fill(&mut salt);
+    let mut iv = [0u8; IV_LEN];
+    rng.fill(&mut iv);
+
+    let derived_key = derive_key(password, &salt, M_COST, T_COST, P_COST).c(d!())?;
+    let cipher = Aes256Gcm::new(GenericArray::from_slice(&derived_key));
+    let ciphertext = cipher
+        .encrypt(GenericArray::from_slice(&iv), data)
+        .map_err(|_| eg!("Could not encrypt the data"))?;
+
+    let mut res = Vec::with_capacity(HEADER_LEN + ciphertext.len());
+    res.extend_from_slice(MAGIC);
+    res.push(VERSION);
+    res.extend_from_slice(&M_COST.to_le_bytes());
+    res.extend_from_slice(&T_COST.to_le_bytes());
+    res.extend_from_slice(&P_COST.to_le_bytes());
+    res.extend_from_slice(&salt);
+    res.extend_from_slice(&iv);
+    res.extend_from_slice(&ciphertext);
+    Ok(res)
+}
+
+/// Decrypt a container produced by `encrypt`.
+pub fn decrypt(data: &[u8], password: &[u8]) -> Result<Vec<u8>> {
+    if !is_encrypted(data) {
+        return Err(eg!("Invalid wallet container"));
+    }
+    if VERSION != data[4] {
+        return Err(eg!("Unsupported wallet container version"));
+    }
+
+    let param = |i: usize| {
+        let mut bytes = [0u8; 4];
+        bytes.copy_from_slice(&data[(5 + 4 * i)..(9 + 4 * i)]);
+        u32::from_le_bytes(bytes)
+    };
+    let (m_cost, t_cost, p_cost) = (param(0), param(1), param(2));
+
+    let salt = &data[17..(17 + SALT_LEN)];
+    let iv = &data[(17 + SALT_LEN)..HEADER_LEN];
+    let ciphertext = &data[HEADER_LEN..];
+
+    let derived_key = derive_key(password, salt, m_cost, t_cost, p_cost).c(d!())?;
+    let cipher = Aes256Gcm::new(GenericArray::from_slice(&derived_key));
+    cipher
+        .decrypt(GenericArray::from_slice(iv), ciphertext)
+        .map_err(|_| eg!("Wrong password or corrupted data"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn keystore_encryption() {
+        let data = b"zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo wrong";
+        let enc = pnk!(encrypt(data, b"pwd"));
+        assert!(is_encrypted(&enc));
+        assert!(!is_encrypted(data));
+
+        assert_eq!(data.to_vec(), pnk!(decrypt(&enc, b"pwd")));
+        assert!(decrypt(&enc, b"wrong").is_err());
+
+        // oversized params must be rejected before running the KDF
+        let mut evil = enc;
+        evil[5..9].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(decrypt(&evil, b"pwd").is_err());
+
+        let salt = [0u8; SALT_LEN];
+        assert!(derive_key(b"pwd", &salt, 1 + M_COST_MAX, T_COST, P_COST).is_err());
+        assert!(derive_key(b"pwd", &salt, M_COST, 1 + T_COST_MAX, P_COST).is_err());
+        assert!(derive_key(b"pwd", &salt, M_COST, T_COST, 1 + P_COST_MAX).is_err());
+    }
+}
@@ -7,4 +7,5 @@
 
 pub mod api;
 pub mod common;
+pub mod keystore;
 pub mod txn_builder;
@@ -16,13 +16,15 @@ use {
             IssuerPublicKey, Memo, NoReplayToken, Operation, Transaction,
             TransactionBody, TransferAsset, TransferAssetBody, TransferType, TxOutput,
             TxoRef, UpdateMemo, UpdateMemoBody, ASSET_TYPE_FRA, BLACK_HOLE_PUBKEY,
-            TX_FEE_MIN,
+            BLACK_HOLE_PUBKEY_STAKING, TX_FEE_MIN,
         },
         staking::{
             is_valid_tendermint_addr,
             ops::{
                 claim::ClaimOps,
+                consensus_params::UpdateConsensusParamsOps,
                 delegation::DelegationOps,
+                fee_asset::UpdateFeeAssetOps,
                 fra_distribution::FraDistributionOps,
                 governance::{ByzantineKind, GovernanceOps},
                 undelegation::UnDelegationOps,
@@ -212,6 +214,17 @@ impl TransactionBuilder {
     /// As the last operation of any transaction,
     /// add a static fee to the transaction.
     pub fn add_fee(&mut self, inputs: FeeInputs) -> Result<&mut TransactionBuilder> {
+        Self::gen_paid_transfer_op(inputs, &[])
+            .c(d!())
+            .map(move |op| self.add_operation(op))
+    }
+
+    // Spend `inputs` to pay the static fee,
+    // along with some extra outputs, such as the principal of a delegation.
+    fn gen_paid_transfer_op(
+        inputs: FeeInputs,
+        extra_outputs: &[(u64, XfrPublicKey)],
+    ) -> Result<Operation> {
         let mut kps = vec![];
         let mut opb = TransferOperationBuilder::default();
 
@@ -227,33 +240,40 @@ impl TransactionBuilder {
                 })?;
         }
 
-        opb.add_output(
-            &AssetRecordTemplate::with_no_asset_tracing(
-                TX_FEE_MIN,
-                ASSET_TYPE_FRA,
-                AssetRecordType::from_flags(false, false),
-                *BLACK_HOLE_PUBKEY,
-            ),
-            None,
-            None,
-            None,
-        )
-        .c(d!())
-        .and_then(|o| o.balance(None).c(d!()))
-        .and_then(|o| o.create(TransferType::Standard).c(d!()))
-        .and_then(|o| {
-            let cmp = |a: &XfrKeyPair, b: &XfrKeyPair| {
-                a.get_pk().as_bytes().cmp(b.get_pk().as_bytes())
-            };
-            kps.sort_by(cmp);
-            kps.dedup_by(|a, b| matches!(cmp(a, b), Ordering::Equal));
-            for i in kps.iter() {
-                o.sign(i).c(d!())?;
-            }
-            Ok(o)
-        })
-        .and_then(|o| o.transaction().c(d!()))
-        .map(move |op| self.add_operation(op))
+        for (am, pk) in extra_outputs
+            .iter()
+            .copied()
+            .chain([(TX_FEE_MIN, *BLACK_HOLE_PUBKEY)].iter().copied())
+        {
+            opb.add_output(
+                &AssetRecordTemplate::with_no_asset_tracing(
+                    am,
+                    ASSET_TYPE_FRA,
+                    AssetRecordType::from_flags(false, false),
+                    pk,
+                ),
+                None,
+                None,
+                None,
+            )
+            .c(d!())?;
+        }
+
+        opb.balance(None)
+            .c(d!())
+            .and_then(|o| o.create(TransferType::Standard).c(d!()))
+            .and_then(|o| {
+                let cmp = |a: &XfrKeyPair, b: &XfrKeyPair| {
+                    a.get_pk().as_bytes().cmp(b.get_pk().as_bytes())
+                };
+                kps.sort_by(cmp);
+                kps.dedup_by(|a, b| matches!(cmp(a, b), Ordering::Equal));
+                for i in kps.iter() {
+                    o.sign(i).c(d!())?;
+                }
+                Ok(o)
+            })
+            .and_then(|o| o.transaction().c(d!()))
     }
 
     /// SEE [check_fee](ledger::data_model::Transaction::check_fee)
@@ -487,6 +507,62 @@ impl TransactionBuilder {
         self.add_operation(Operation::Delegation(op))
     }
 
+    /// Add a delegation operation along with the transfer operation
+    /// paying its principal to BLACK_HOLE_PUBKEY_STAKING,
+    /// the principal and the static fee are both spent from `inputs`.
+    ///
+    /// All the inputs must be owned by the delegator,
+    /// and must refer to txos that already exist on the ledger.
+    pub fn add_delegation_with_fee(
+        &mut self,
+        keypair: &XfrKeyPair,
+        amount: u64,
+        validator: TendermintAddr,
+        inputs: FeeInputs,
+    ) -> Result<&mut Self> {
+        for i in inputs.inner.iter() {
+            if i.kp.get_pk() != keypair.get_pk() {
+                return Err(eg!("inputs must be owned by the delegator"));
+            }
+            if !matches!(i.tr, TxoRef::Absolute(_)) {
+                return Err(eg!("inputs must be absolute txo references"));
+            }
+        }
+
+        Self::gen_paid_transfer_op(inputs, &[(amount, *BLACK_HOLE_PUBKEY_STAKING)])
+            .c(d!())
+            .map(move |principal_op| {
+                self.add_operation(principal_op);
+                self.add_operation_delegation(keypair, amount, validator)
+            })
+    }
+
+    /// Add a (partial) un-delegation operation,
+    /// the static fee is spent from `inputs`.
+    pub fn add_undelegation_with_fee(
+        &mut self,
+        keypair: &XfrKeyPair,
+        pu: Option<PartialUnDelegation>,
+        inputs: FeeInputs,
+    ) -> Result<&mut Self> {
+        self.add_fee(inputs)
+            .c(d!())
+            .map(move |b| b.add_operation_undelegation(keypair, pu))
+    }
+
+    /// Add a claim operation,
+    /// the static fee is spent from `inputs`.
+    pub fn add_claim_with_fee(
+        &mut self,
+        keypair: &XfrKeyPair,
+        am: Option<u64>,
+        inputs: FeeInputs,
+    ) -> Result<&mut Self> {
+        self.add_fee(inputs)
+            .c(d!())
+            .map(move |b| b.add_operation_claim(keypair, am))
+    }
+
     /// Add a operation to updating staker memo and commission_rate
     pub fn add_operation_update_staker(
         &mut self,
@@ -605,6 +681,39 @@ impl TransactionBuilder {
         .map(move |op| self.add_operation(Operation::Governance(op)))
     }
 
+    /// Add a operation to update the block limits of tendermint
+    /// at specified block height.
+    pub fn add_operation_update_consensus_params(
+        &mut self,
+        kps: &[&XfrKeyPair],
+        h: BlockHeight,
+        max_bytes: i64,
+        max_gas: i64,
+    ) -> Result<&mut Self> {
+        UpdateConsensusParamsOps::new(
+            kps,
+            h,
+            max_bytes,
+            max_gas,
+            self.txn.body.no_replay_token,
+        )
+        .c(d!())
+        .map(move |op| self.add_operation(Operation::UpdateConsensusParams(op)))
+    }
+
+    /// Add a operation to whitelist a fee asset,
+    /// or remove it from the whitelist if `rate` is `None`.
+    pub fn add_operation_update_fee_asset(
+        &mut self,
+        kps: &[&XfrKeyPair],
+        code: AssetTypeCode,
+        rate: Option<[u64; 2]>,
+    ) -> Result<&mut Self> {
+        UpdateFeeAssetOps::new(kps, code, rate, self.txn.body.no_replay_token)
+            .c(d!())
+            .map(move |op| self.add_operation(Operation::UpdateFeeAsset(op)))
+    }
+
     /// Add a operation update the validator set at specified block height.
     pub fn add_operation_update_validator(
         &mut self,
@@ -1101,7 +1210,7 @@ impl TransferOperationBuilder {
 mod tests {
     use {
         super::*,
-        ledger::data_model::{TxnEffect, TxoRef},
+        ledger::data_model::{TxnEffect, TxoRef, TxoSID},
         ledger::store::{utils::fra_gen_initial_tx, LedgerState},
         rand_chacha::ChaChaRng,
         rand_core::SeedableRng,
@@ -1109,6 +1218,7 @@ mod tests {
         zei::xfr::asset_record::AssetRecordType::NonConfidentialAmount_NonConfidentialAssetType,
         zei::xfr::asset_record::{build_blind_asset_record, open_blind_asset_record},
         zei::xfr::sig::XfrKeyPair,
+        zei::xfr::structs::XfrAmount,
     };
 
     // Defines an asset type
@@ -1455,4 +1565,122 @@ mod tests {
         let mut block = ledger.start_block().unwrap();
         assert!(ledger.apply_transaction(&mut block, effect).is_err());
     }
+
+    #[test]
+    fn test_add_staking_ops_with_fee() {
+        let mut prng = ChaChaRng::from_entropy();
+        let params = PublicParams::default();
+        let kp = XfrKeyPair::generate(&mut prng);
+        let other_kp = XfrKeyPair::generate(&mut prng);
+
+        let fee_inputs =
+            |prng: &mut ChaChaRng, am: u64, tr: TxoRef, owner: &XfrKeyPair| {
+                let template = AssetRecordTemplate::with_no_asset_tracing(
+                    am,
+                    ASSET_TYPE_FRA,
+                    NonConfidentialAmount_NonConfidentialAssetType,
+                    owner.get_pk(),
+                );
+                let (record, _, om) =
+                    build_blind_asset_record(prng, &params.pc_gens, &template, vec![]);
+                let mut fi = FeeInputs::new();
+                fi.append(
+                    am,
+                    tr,
+                    TxOutput {
+                        id: None,
+                        record,
+                        lien: None,
+                    },
+                    om,
+                    owner.get_sk().into_keypair(),
+                );
+                fi
+            };
+
+        let principal = 100 * TX_FEE_MIN;
+        let validator = "0000000000000000000000000000000000000000".to_owned();
+
+        let mut builder = TransactionBuilder::from_seq_id(1);
+        pnk!(builder.add_delegation_with_fee(
+            &kp,
+            principal,
+            validator.clone(),
+            fee_inputs(
+                &mut prng,
+                principal + TX_FEE_MIN,
+                TxoRef::Absolute(TxoSID(0)),
+                &kp
+            ),
+        ));
+        let ops = &builder.get_transaction().body.operations;
+        assert_eq!(2, ops.len());
+        assert!(matches!(ops[1], Operation::Delegation(_)));
+        if let Operation::TransferAsset(ref t) = ops[0] {
+            assert!(t.body.outputs.iter().any(|o| {
+                o.record.public_key == *BLACK_HOLE_PUBKEY_STAKING
+                    && matches!(o.record.amount, XfrAmount::NonConfidential(am) if am == principal)
+            }));
+        } else {
+            panic!("the principal must be paid by a transfer operation");
+        }
+
+        // the inputs are not enough to pay both the principal and the fee
+        let mut builder = TransactionBuilder::from_seq_id(1);
+        assert!(builder
+            .add_delegation_with_fee(
+                &kp,
+                principal,
+                validator.clone(),
+                fee_inputs(&mut prng, principal, TxoRef::Absolute(TxoSID(0)), &kp),
+            )
+            .is_err());
+        assert!(builder.get_transaction().body.operations.is_empty());
+
+        // the inputs are not owned by the delegator
+        let mut builder = TransactionBuilder::from_seq_id(1);
+        assert!(builder
+            .add_delegation_with_fee(
+                &kp,
+                principal,
+                validator.clone(),
+                fee_inputs(
+                    &mut prng,
+                    principal + TX_FEE_MIN,
+                    TxoRef::Absolute(TxoSID(0)),
+                    &other_kp
+                ),
+            )
+            .is_err());
+        assert!(builder.get_transaction().body.operations.is_empty());
+
+        // the inputs do not exist on the ledger
+        let mut builder = TransactionBuilder::from_seq_id(1);
+        assert!(builder
+            .add_delegation_with_fee(
+                &kp,
+                principal,
+                validator,
+                fee_inputs(&mut prng, principal + TX_FEE_MIN, TxoRef::Relative(0), &kp),
+            )
+            .is_err());
+        assert!(builder.get_transaction().body.operations.is_empty());
+
+        let mut builder = TransactionBuilder::from_seq_id(1);
+        pnk!(builder.add_undelegation_with_fee(
+            &kp,
+            None,
+            fee_inputs(&mut prng, TX_FEE_MIN, TxoRef::Absolute(TxoSID(0)), &kp)
+        ));
+        pnk!(builder.add_claim_with_fee(
+            &kp,
+            None,
+            fee_inputs(&mut prng, TX_FEE_MIN, TxoRef::Absolute(TxoSID(1)), &kp)
+        ));
+        assert!(builder.check_fee());
+        let ops = &builder.get_transaction().body.operations;
+        assert_eq!(4, ops.len());
+        assert!(matches!(ops[1], Operation::UnDelegation(_)));
+        assert!(matches!(ops[3], Operation::Claim(_)));
+    }
 }
@@ -29,10 +29,13 @@ use {
         CredUserPublicKey, CredUserSecretKey, Credential as PlatformCredential,
     },
     cryptohash::sha256,
-    finutils::txn_builder::{
-        FeeInput as PlatformFeeInput, FeeInputs as PlatformFeeInputs,
-        TransactionBuilder as PlatformTransactionBuilder,
-        TransferOperationBuilder as PlatformTransferOperationBuilder,
+    finutils::{
+        keystore,
+        txn_builder::{
+            FeeInput as PlatformFeeInput, FeeInputs as PlatformFeeInputs,
+            TransactionBuilder as PlatformTransactionBuilder,
+            TransferOperationBuilder as PlatformTransferOperationBuilder,
+        },
     },
     globutils::{wallet, HashOf},
     ledger::{
@@ -428,6 +431,54 @@ impl TransactionBuilder {
         Ok(self)
     }
 
+    /// Delegate `amount` FRA to a validator,
+    /// the principal and the static fee are both paid from `inputs`.
+    pub fn add_delegation_with_fee(
+        mut self,
+        keypair: &XfrKeyPair,
+        amount: u64,
+        validator: TendermintAddr,
+        inputs: FeeInputs,
+    ) -> Result<TransactionBuilder, JsValue> {
+        self.get_builder_mut()
+            .add_delegation_with_fee(keypair, amount, validator, inputs.into())
+            .c(d!())
+            .map_err(error_to_jsvalue)?;
+        Ok(self)
+    }
+
+    /// Un-delegate all the FRA, the static fee is paid from `inputs`.
+    pub fn add_undelegation_with_fee(
+        mut self,
+        keypair: &XfrKeyPair,
+        inputs: FeeInputs,
+    ) -> Result<TransactionBuilder, JsValue> {
+        self.get_builder_mut()
+            .add_undelegation_with_fee(keypair, None, inputs.into())
+            .c(d!())
+            .map_err(error_to_jsvalue)?;
+        Ok(self)
+    }
+
+    /// Claim `am` FRA of rewards, all the rewards will be claimed if `am` is zero,
+    /// the static fee is paid from `inputs`.
+    pub fn add_claim_with_fee(
+        mut self,
+        keypair: &XfrKeyPair,
+        am: u64,
+        inputs: FeeInputs,
+    ) -> Result<TransactionBuilder, JsValue> {
+        self.get_builder_mut()
+            .add_claim_with_fee(
+                keypair,
+                if 0 == am { None } else { Some(am) },
+                inputs.into(),
+            )
+            .c(d!())
+            .map_err(error_to_jsvalue)?;
+        Ok(self)
+    }
+
     #[allow(missing_docs)]
     pub fn add_operation_undelegate(
         mut self,
@@ -1228,6 +1279,41 @@ pub fn decryption_pbkdf2_aes256gcm(enc_key_pair: Vec<u8>, password: String) -> S
     String::from_utf8(plaintext).unwrap_or_else(|_| "".to_string())
 }
 
+/// Encrypt a keypair into a versioned container,
+/// the encryption key is derived from the password by Argon2id.
+#[wasm_bindgen]
+pub fn encryption_argon2_aes256gcm(
+    key_pair: String,
+    password: String,
+) -> Result<Vec<u8>, JsValue> {
+    keystore::encrypt(key_pair.as_bytes(), password.as_bytes()).map_err(error_to_jsvalue)
+}
+
+/// Decrypt a container produced by `encryption_argon2_aes256gcm`.
+#[wasm_bindgen]
+pub fn decryption_argon2_aes256gcm(
+    enc_key_pair: Vec<u8>,
+    password: String,
+) -> Result<String, JsValue> {
+    keystore::decrypt(&enc_key_pair, password.as_bytes())
+        .map_err(error_to_jsvalue)
+        .and_then(|plaintext| String::from_utf8(plaintext).map_err(error_to_jsvalue))
+}
+
+/// Re-encrypt a keypair produced by `encryption_pbkdf2_aes256gcm`
+/// into the container of `encryption_argon2_aes256gcm`.
+#[wasm_bindgen]
+pub fn migrate_pbkdf2_to_argon2_aes256gcm(
+    enc_key_pair: Vec<u8>,
+    password: String,
+) -> Result<Vec<u8>, JsValue> {
+    let key_pair = decryption_pbkdf2_aes256gcm(enc_key_pair, password.clone());
+    if key_pair.is_empty() {
+        return Err(JsValue::from_str("Wrong password or corrupted data"));
+    }
+    encryption_argon2_aes256gcm(key_pair, password)
+}
+
 #[wasm_bindgen]
 #[allow(missing_docs)]
 pub fn create_keypair_from_secret(sk_str: String) -> Option<XfrKeyPair> {
@@ -1262,6 +1348,31 @@ pub fn generate_mnemonic_custom(wordslen: u8, lang: &str) -> Result<String, JsVa
         .map_err(error_to_jsvalue)
 }
 
+/// Check whether a mnemonic is valid without deriving any key from it,
+/// the error message tells an unknown word, a bad checksum or a wrong words length,
+/// `lang` can be "auto".
+#[wasm_bindgen]
+pub fn validate_mnemonic(phrase: &str, lang: &str) -> Result<(), JsValue> {
+    wallet::validate_mnemonic(phrase, lang).map_err(error_to_jsvalue)
+}
+
+/// Detect the language of a mnemonic, the result can be used as the `lang`
+/// of the restore functions, which also accept "auto" directly.
+#[wasm_bindgen]
+pub fn detect_mnemonic_lang(phrase: &str) -> Result<String, JsValue> {
+    wallet::detect_mnemonic_lang(phrase)
+        .c(d!())
+        .map(|l| l.to_owned())
+        .map_err(error_to_jsvalue)
+}
+
+/// Sign the nonce got from the `ownership_challenge` API of a node,
+/// post the result to its `ownership_proof` API to prove the ownership of `kp`.
+#[wasm_bindgen]
+pub fn sign_ownership_challenge(kp: &XfrKeyPair, nonce: &str) -> String {
+    wallet::sign_ownership_challenge(kp, nonce)
+}
+
 /// Use this struct to express a Bip44/Bip49 path.
 #[wasm_bindgen]
 pub struct BipPath {
@@ -1401,6 +1512,24 @@ mod test {
         assert_eq!(key_pair, dec_key_pair);
     }
 
+    #[test]
+    fn t_keypair_encryption_argon2() {
+        let key_pair = "hello world".to_string();
+        let password = "12345".to_string();
+
+        let enc =
+            encryption_argon2_aes256gcm(key_pair.clone(), password.clone()).unwrap();
+        assert!(keystore::is_encrypted(&enc));
+        let dec_key_pair = decryption_argon2_aes256gcm(enc, password.clone()).unwrap();
+        assert_eq!(key_pair, dec_key_pair);
+
+        let old_enc = encryption_pbkdf2_aes256gcm(key_pair.clone(), password.clone());
+        let new_enc =
+            migrate_pbkdf2_to_argon2_aes256gcm(old_enc, password.clone()).unwrap();
+        let dec_key_pair = decryption_argon2_aes256gcm(new_enc, password).unwrap();
+        assert_eq!(key_pair, dec_key_pair);
+    }
+
     #[test]
     fn t_create_keypair_from_secret() {
         let kp = new_keypair();
@@ -8,7 +8,8 @@ use {
         staking::{
             self,
             ops::{
-                claim::ClaimOps, delegation::DelegationOps,
+                claim::ClaimOps, consensus_params::UpdateConsensusParamsOps,
+                delegation::DelegationOps, fee_asset::UpdateFeeAssetOps,
                 fra_distribution::FraDistributionOps, governance::GovernanceOps,
                 undelegation::UnDelegationOps, update_staker::UpdateStakerOps,
                 update_validator::UpdateValidatorOps,
@@ -21,9 +22,9 @@ use {
     rand_chacha::{ChaCha20Rng, ChaChaRng},
     rand_core::SeedableRng,
     ruc::*,
-    serde::Serialize,
+    serde::{Deserialize, Serialize},
     std::{
-        collections::{HashMap, HashSet},
+        collections::{BTreeMap, BTreeSet},
         sync::Arc,
     },
     zei::{
@@ -52,27 +53,27 @@ pub struct TxnEffect {
     /// Internally-spent TXOs are None, UTXOs are Some(...)
     pub txos: Vec<Option<TxOutput>>,
     /// Which TXOs this consumes
-    pub input_txos: HashMap<TxoSID, TxOutput>,
+    pub input_txos: BTreeMap<TxoSID, TxOutput>,
     /// List of internally-spent TXOs. This does not include input txos;
     pub internally_spent_txos: Vec<TxOutput>,
     /// Which new asset types this defines
-    pub new_asset_codes: HashMap<AssetTypeCode, AssetType>,
+    pub new_asset_codes: BTreeMap<AssetTypeCode, AssetType>,
     /// Which new TXO issuance sequence numbers are used, in sorted order
     /// The vec should be nonempty unless this asset code is being created in
     /// this transaction.
-    pub new_issuance_nums: HashMap<AssetTypeCode, Vec<u64>>,
+    pub new_issuance_nums: BTreeMap<AssetTypeCode, Vec<u64>>,
     /// Which public key is being used to issue each asset type
-    pub issuance_keys: HashMap<AssetTypeCode, IssuerPublicKey>,
+    pub issuance_keys: BTreeMap<AssetTypeCode, IssuerPublicKey>,
     /// New issuance amounts
-    pub issuance_amounts: HashMap<AssetTypeCode, u64>,
+    pub issuance_amounts: BTreeMap<AssetTypeCode, u64>,
     /// Asset types that have issuances with confidential outputs. Issuances cannot be confidential
     /// if there is an issuance cap
-    pub confidential_issuance_types: HashSet<AssetTypeCode>,
+    pub confidential_issuance_types: BTreeSet<AssetTypeCode>,
     /// Non-confidential asset types involved in confidential transfers
-    pub confidential_transfer_inputs: HashSet<AssetTypeCode>,
+    pub confidential_transfer_inputs: BTreeSet<AssetTypeCode>,
 
     /// Asset types involved in this tx
-    pub asset_types_involved: HashSet<AssetTypeCode>,
+    pub asset_types_involved: BTreeSet<AssetTypeCode>,
     /// Memo updates
     pub memo_updates: Vec<(AssetTypeCode, XfrPublicKey, Memo)>,
 
@@ -85,11 +86,15 @@ pub struct TxnEffect {
     /// Staking operations
     pub governances: Vec<GovernanceOps>,
     /// Staking operations
-    pub update_validators: HashMap<staking::BlockHeight, UpdateValidatorOps>,
+    pub update_validators: BTreeMap<staking::BlockHeight, UpdateValidatorOps>,
     /// Staking operations
     pub fra_distributions: Vec<FraDistributionOps>,
     /// Staking operations
     pub update_stakers: Vec<UpdateStakerOps>,
+    /// Staking operations
+    pub update_consensus_params: Vec<UpdateConsensusParamsOps>,
+    /// Staking operations
+    pub update_fee_assets: Vec<UpdateFeeAssetOps>,
 }
 
 impl TxnEffect {
@@ -186,6 +191,14 @@ impl TxnEffect {
                     check_nonce!(i);
                     te.fra_distributions.push(i.clone());
                 }
+                Operation::UpdateConsensusParams(i) => {
+                    check_nonce!(i);
+                    te.update_consensus_params.push(i.clone());
+                }
+                Operation::UpdateFeeAsset(i) => {
+                    check_nonce!(i);
+                    te.update_fee_assets.push(i.clone());
+                }
             }
         }
 
@@ -387,7 +400,7 @@ impl TxnEffect {
         match trn.body.transfer_type {
             TransferType::DebtSwap => {}
             TransferType::Standard => {
-                let mut input_keys = HashSet::new();
+                let mut input_keys = BTreeSet::new();
                 // (1a) all body signatures are valid
                 for sig in &trn.body_signatures {
                     if !trn.body.verify_body_signature(sig) {
@@ -413,7 +426,7 @@ impl TxnEffect {
             }
         }
         // (3)
-        let mut input_types = HashSet::new();
+        let mut input_types = BTreeSet::new();
         for ((inp, record), lien) in trn
             .body
             .inputs
@@ -539,19 +552,19 @@ pub struct BlockEffect {
     /// Should line up element-wise with `txns`
     pub txos: Vec<Vec<Option<TxOutput>>>,
     /// Which TXOs this consumes
-    pub input_txos: HashMap<TxoSID, TxOutput>,
+    pub input_txos: BTreeMap<TxoSID, TxOutput>,
     /// Which new asset types this defines
-    pub new_asset_codes: HashMap<AssetTypeCode, AssetType>,
+    pub new_asset_codes: BTreeMap<AssetTypeCode, AssetType>,
     /// Which new TXO issuance sequence numbers are used, in sorted order
     /// The vec should be nonempty unless this asset code is being created in
     /// this transaction.
-    pub new_issuance_nums: HashMap<AssetTypeCode, Vec<u64>>,
+    pub new_issuance_nums: BTreeMap<AssetTypeCode, Vec<u64>>,
     /// New issuance amounts
-    pub issuance_amounts: HashMap<AssetTypeCode, u64>,
+    pub issuance_amounts: BTreeMap<AssetTypeCode, u64>,
     /// Which public key is being used to issue each asset type
-    pub issuance_keys: HashMap<AssetTypeCode, IssuerPublicKey>,
+    pub issuance_keys: BTreeMap<AssetTypeCode, IssuerPublicKey>,
     /// Memo updates
-    pub memo_updates: HashMap<AssetTypeCode, Memo>,
+    pub memo_updates: BTreeMap<AssetTypeCode, Memo>,
     /// counter for consensus integration; will add to a running count when applied.
     pub pulse_count: u64,
     /// simulator for safety
@@ -691,6 +704,14 @@ impl BlockEffect {
                 .c(d!())?;
         }
 
+        for i in txn_effect.update_consensus_params.iter() {
+            i.check_run(&mut self.staking_simulator).c(d!())?;
+        }
+
+        for i in txn_effect.update_fee_assets.iter() {
+            i.check_run(&mut self.staking_simulator).c(d!())?;
+        }
+
         Ok(())
     }
 
@@ -700,9 +721,37 @@ impl BlockEffect {
         HashOf::new(&self.txns)
     }
 
+    /// Hash all the writes of this block to the ledger status in a canonical order,
+    /// so the result can be compared across nodes.
+    pub fn compute_write_set_hash(&self) -> HashOf<BlockWriteSet> {
+        HashOf::new(&BlockWriteSet {
+            no_replay_tokens: self.no_replay_tokens.clone(),
+            txos: self.txos.clone(),
+            input_txos: self.input_txos.clone(),
+            new_asset_codes: self.new_asset_codes.clone(),
+            new_issuance_nums: self.new_issuance_nums.clone(),
+            issuance_amounts: self.issuance_amounts.clone(),
+            memo_updates: self.memo_updates.clone(),
+        })
+    }
+
     #[inline(always)]
     #[allow(missing_docs)]
     pub fn get_staking_simulator_mut(&mut self) -> &mut staking::Staking {
         &mut self.staking_simulator
     }
 }
+
+/// The writes of a `BlockEffect` in a canonical order,
+/// SEE `BlockEffect::compute_write_set_hash`.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct BlockWriteSet {
+    pub no_replay_tokens: Vec<NoReplayToken>,
+    pub txos: Vec<Vec<Option<TxOutput>>>,
+    pub input_txos: BTreeMap<TxoSID, TxOutput>,
+    pub new_asset_codes: BTreeMap<AssetTypeCode, AssetType>,
+    pub new_issuance_nums: BTreeMap<AssetTypeCode, Vec<u64>>,
+    pub issuance_amounts: BTreeMap<AssetTypeCode, u64>,
+    pub memo_updates: BTreeMap<AssetTypeCode, Memo>,
+}
@@ -9,12 +9,13 @@ mod __trash__;
 mod effects;
 mod test;
 
-pub use effects::{BlockEffect, TxnEffect};
+pub use effects::{BlockEffect, BlockWriteSet, TxnEffect};
 
 use {
     crate::staking::{
         ops::{
-            claim::ClaimOps, delegation::DelegationOps,
+            claim::ClaimOps, consensus_params::UpdateConsensusParamsOps,
+            delegation::DelegationOps, fee_asset::UpdateFeeAssetOps,
             fra_distribution::FraDistributionOps, governance::GovernanceOps,
             mint_fra::MintFraOps, undelegation::UnDelegationOps,
             update_staker::UpdateStakerOps, update_validator::UpdateValidatorOps,
@@ -33,7 +34,7 @@ use {
     ruc::*,
     serde::{de::Visitor, Deserialize, Deserializer, Serialize, Serializer},
     std::{
-        collections::{HashMap, HashSet},
+        collections::{BTreeMap, BTreeSet},
         convert::TryFrom,
         fmt,
         hash::{Hash, Hasher},
@@ -78,7 +79,7 @@ pub struct Code {
 }
 
 #[inline(always)]
-fn is_default<T: Default + PartialEq>(x: &T) -> bool {
+pub(crate) fn is_default<T: Default + PartialEq>(x: &T) -> bool {
     x == &T::default()
 }
 
@@ -445,9 +446,9 @@ pub struct SignatureRules {
 impl SignatureRules {
     /// Returns Ok(()) if the sum of weights of the keys in keyset reaches the threshold.
     /// Keyset must store XfrPublicKeys in byte form.
-    pub fn check_signature_set(&self, keyset: &HashSet<Vec<u8>>) -> Result<()> {
+    pub fn check_signature_set(&self, keyset: &BTreeSet<Vec<u8>>) -> Result<()> {
         let mut sum: u64 = 0;
-        let mut weight_map = HashMap::new();
+        let mut weight_map = BTreeMap::new();
         // Convert to map
         for (key, weight) in self.weights.iter() {
             weight_map.insert(key.as_bytes(), *weight);
@@ -712,7 +713,19 @@ pub struct BlockSID(pub usize);
 
 /// An ephemeral index for a transaction (with a different newtype so that
 /// it's harder to mix up)
-#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Deserialize,
+    Eq,
+    Hash,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Serialize,
+)]
 pub struct TxnTempSID(pub usize);
 
 #[allow(missing_docs)]
@@ -1188,6 +1201,10 @@ pub enum Operation {
     FraDistribution(FraDistributionOps),
     /// Coinbase operation
     MintFra(MintFraOps),
+    /// Update the block limits of tendermint
+    UpdateConsensusParams(UpdateConsensusParamsOps),
+    /// Update the whitelist of fee assets
+    UpdateFeeAsset(UpdateFeeAssetOps),
 }
 
 fn set_no_replay_token(op: &mut Operation, no_replay_token: NoReplayToken) {
@@ -1213,6 +1230,12 @@ fn set_no_replay_token(op: &mut Operation, no_replay_token: NoReplayToken) {
         Operation::Governance(i) => {
             i.set_nonce(no_replay_token);
         }
+        Operation::UpdateConsensusParams(i) => {
+            i.set_nonce(no_replay_token);
+        }
+        Operation::UpdateFeeAsset(i) => {
+            i.set_nonce(no_replay_token);
+        }
         Operation::UpdateMemo(i) => i.body.no_replay_token = no_replay_token,
         _ => {}
     }
@@ -1544,6 +1567,24 @@ lazy_static! {
 /// see [**mainnet-v0.1 defination**](https://www.notion.so/findora/Transaction-Fees-Analysis-d657247b70f44a699d50e1b01b8a2287)
 pub const TX_FEE_MIN: u64 = 1_0000;
 
+/// The minimal fee in units of an asset,
+/// `[x, y]` means `x` units of the asset are worth `y` units of FRA.
+#[inline(always)]
+pub fn fee_in_asset(rate: [u64; 2]) -> Option<u64> {
+    convert_fee(TX_FEE_MIN, rate)
+}
+
+#[inline(always)]
+fn convert_fee(fee: u64, rate: [u64; 2]) -> Option<u64> {
+    if 0 == rate[1] {
+        return None;
+    }
+    (fee as u128)
+        .checked_mul(rate[0] as u128)
+        .map(|n| (n + rate[1] as u128 - 1) / rate[1] as u128)
+        .and_then(|n| u64::try_from(n).ok())
+}
+
 impl Transaction {
     #[inline(always)]
     #[allow(missing_docs)]
@@ -1560,6 +1601,15 @@ impl Transaction {
         self.check_fee() && !self.is_coinbase_tx()
     }
 
+    /// All-in-one checker, whitelisted fee assets are accepted.
+    #[inline(always)]
+    pub fn valid_in_abci_with_fee_assets(
+        &self,
+        fee_assets: &BTreeMap<String, [u64; 2]>,
+    ) -> bool {
+        self.check_fee_with_assets(fee_assets) && !self.is_coinbase_tx()
+    }
+
     /// A simple fee checker
     ///
     /// The check logic is as follows:
@@ -1568,7 +1618,30 @@ impl Transaction {
     /// - Fee destination == BLACK_HOLE_PUBKEY
     /// - A transaction with an `Operation` of defining/issuing FRA need NOT fee
     /// - A transaction with all addresses of inputs equal to BLACK_HOLE_PUBKEY need NOT fee
+    #[inline(always)]
     pub fn check_fee(&self) -> bool {
+        self.check_fee_with_assets(&BTreeMap::new())
+    }
+
+    /// Same as `check_fee`, but a fee can also be paid
+    /// in the whitelisted assets of `fee_assets`,
+    /// which maps the base64 code of an asset to its rate against FRA,
+    /// the asset is burned as it is, the minimal amount is rounded up.
+    #[inline(always)]
+    pub fn check_fee_with_assets(
+        &self,
+        fee_assets: &BTreeMap<String, [u64; 2]>,
+    ) -> bool {
+        self.check_fee_above(fee_assets, TX_FEE_MIN)
+    }
+
+    /// Same as `check_fee_with_assets`, but with a custom minimal fee,
+    /// used by nodes that want a higher fee than the consensus one.
+    pub fn check_fee_above(
+        &self,
+        fee_assets: &BTreeMap<String, [u64; 2]>,
+        fee_min: u64,
+    ) -> bool {
         // This method can not completely solve the DOS risk,
         // we should further limit the number of txo[s] in every operation.
         //
@@ -1579,12 +1652,19 @@ impl Transaction {
                 if let Operation::TransferAsset(ref x) = ops {
                     return x.body.outputs.iter().any(|o| {
                         if let XfrAssetType::NonConfidential(ty) = o.record.asset_type {
-                            if ty == ASSET_TYPE_FRA
-                                && *BLACK_HOLE_PUBKEY == o.record.public_key
-                            {
+                            if *BLACK_HOLE_PUBKEY == o.record.public_key {
                                 if let XfrAmount::NonConfidential(am) = o.record.amount {
-                                    if am > (TX_FEE_MIN - 1) {
-                                        return true;
+                                    let fee_min = if ty == ASSET_TYPE_FRA {
+                                        Some(fee_min)
+                                    } else {
+                                        fee_assets
+                                            .get(&AssetTypeCode { val: ty }.to_base64())
+                                            .and_then(|rate| convert_fee(fee_min, *rate))
+                                    };
+                                    if let Some(fee_min) = fee_min {
+                                        if am >= fee_min {
+                                            return true;
+                                        }
                                     }
                                 }
                             }
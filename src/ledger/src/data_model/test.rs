@@ -377,3 +377,94 @@ fn test_check_fee() {
     tx.add_operation(invalid_destination_not_black_hole);
     assert!(tx.check_fee());
 }
+
+#[test]
+fn test_check_fee_with_assets() {
+    let code = AssetTypeCode {
+        val: ZeiAssetType([9; ASSET_TYPE_LENGTH]),
+    };
+    // 3 units of the asset are worth 2 units of FRA
+    let fee_assets = map! {B code.to_base64() => [3, 2]};
+    let fee_min = pnk!(fee_in_asset([3, 2]));
+    assert_eq!(fee_min, TX_FEE_MIN / 2 * 3);
+    assert_eq!(fee_in_asset([1, 3]), Some(TX_FEE_MIN / 3 + 1));
+    assert!(fee_in_asset([1, 0]).is_none());
+    assert!(fee_in_asset([u64::MAX, 1]).is_none());
+
+    let mut tx = gen_sample_tx();
+    tx.add_operation(gen_fee_operation(
+        Some(fee_min - 1),
+        Some(code.val),
+        *BLACK_HOLE_PUBKEY,
+    ));
+    assert!(!tx.check_fee_with_assets(&fee_assets));
+
+    let mut tx = gen_sample_tx();
+    tx.add_operation(gen_fee_operation(
+        Some(fee_min),
+        Some(code.val),
+        *BLACK_HOLE_PUBKEY,
+    ));
+    assert!(!tx.check_fee());
+    assert!(tx.check_fee_with_assets(&fee_assets));
+    assert!(tx.valid_in_abci_with_fee_assets(&fee_assets));
+
+    // a higher local minimum
+    assert!(tx.check_fee_above(&fee_assets, TX_FEE_MIN));
+    assert!(!tx.check_fee_above(&fee_assets, TX_FEE_MIN + 1));
+}
+
+#[test]
+fn test_check_fee_with_assets_rounds_up() {
+    let code = AssetTypeCode {
+        val: ZeiAssetType([7; ASSET_TYPE_LENGTH]),
+    };
+    // 2 units of the asset are worth 3 units of FRA,
+    // the exact fee is `TX_FEE_MIN * 2 / 3`, which is not an integer
+    let fee_assets = map! {B code.to_base64() => [2, 3]};
+    assert_ne!(0, TX_FEE_MIN * 2 % 3);
+    let fee_min = TX_FEE_MIN * 2 / 3 + 1;
+    assert_eq!(fee_in_asset([2, 3]), Some(fee_min));
+
+    let mut tx = gen_sample_tx();
+    tx.add_operation(gen_fee_operation(
+        Some(fee_min - 1),
+        Some(code.val),
+        *BLACK_HOLE_PUBKEY,
+    ));
+    assert!(!tx.check_fee_with_assets(&fee_assets));
+
+    let mut tx = gen_sample_tx();
+    tx.add_operation(gen_fee_operation(
+        Some(fee_min),
+        Some(code.val),
+        *BLACK_HOLE_PUBKEY,
+    ));
+    assert!(tx.check_fee_with_assets(&fee_assets));
+}
+
+#[test]
+fn test_check_fee_above_in_base_units() {
+    // the local minimum is counted in base units, 1 FRA is `10^FRA_DECIMALS` of them
+    let one_fra = 10_u64.pow(FRA_DECIMALS as u32);
+    let fee_assets = map! {B};
+
+    let mut tx = gen_sample_tx();
+    tx.add_operation(gen_fee_operation(
+        Some(one_fra),
+        Some(ASSET_TYPE_FRA),
+        *BLACK_HOLE_PUBKEY,
+    ));
+    assert!(tx.check_fee_above(&fee_assets, one_fra));
+    assert!(!tx.check_fee_above(&fee_assets, one_fra + 1));
+
+    // `1` means one base unit, not one FRA
+    let mut tx = gen_sample_tx();
+    tx.add_operation(gen_fee_operation(
+        Some(TX_FEE_MIN),
+        Some(ASSET_TYPE_FRA),
+        *BLACK_HOLE_PUBKEY,
+    ));
+    assert!(tx.check_fee_above(&fee_assets, 1));
+    assert!(!tx.check_fee_above(&fee_assets, one_fra));
+}
@@ -4,28 +4,110 @@
 
 use {
     super::{
-        td_addr_to_bytes, BlockHeight, Power, Validator, ValidatorKind,
+        td_addr_to_bytes, BlockHeight, Power, Validator, ValidatorData, ValidatorKind,
         STAKING_VALIDATOR_MIN_POWER,
     },
+    globutils::wallet,
     indexmap::IndexMap,
     ruc::*,
     serde::{Deserialize, Serialize},
-    std::convert::TryFrom,
+    std::{collections::BTreeSet, convert::TryFrom},
+    zei::{
+        serialization::ZeiFromToBytes,
+        xfr::sig::{XfrKeyPair, XfrPublicKey, XfrSignature},
+    },
 };
 
 // The initial power of an initor.
 const DEFAULT_POWER: Power = STAKING_VALIDATOR_MIN_POWER;
 
 /// Generate config during compiling time.
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct InitialValidatorInfo {
     height: Option<BlockHeight>,
     /// predefined validators
     pub valiators: Vec<ValidatorStr>,
+    /// signatures of the bootstrap keys on the validator-set
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub signatures: Vec<BootstrapSig>,
+}
+
+/// A signature of a bootstrap key on the initial validator-set.
+#[derive(Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct BootstrapSig {
+    /// `XfrPublicKey` in base64 format
+    pub pubkey: String,
+    /// `XfrSignature` in base64 format
+    pub sig: String,
+}
+
+impl InitialValidatorInfo {
+    // the signatures themselves are excluded from the signed content
+    fn signing_payload(&self) -> Result<Vec<u8>> {
+        serde_json::to_vec(&(&self.height, &self.valiators)).c(d!())
+    }
+
+    /// Sign the validator-set with a bootstrap key,
+    /// an old signature of the same key will be replaced.
+    pub fn sign(&mut self, kp: &XfrKeyPair) -> Result<()> {
+        let payload = self.signing_payload().c(d!())?;
+        let pubkey = wallet::public_key_to_base64(kp.get_pk_ref());
+        let sig = base64::encode_config(
+            &kp.get_sk_ref()
+                .sign(&payload, kp.get_pk_ref())
+                .zei_to_bytes(),
+            base64::URL_SAFE,
+        );
+
+        self.signatures.retain(|s| s.pubkey != pubkey);
+        self.signatures.push(BootstrapSig { pubkey, sig });
+
+        Ok(())
+    }
+
+    /// Make sure the validator-set has been signed
+    /// by at least `threshold` keys of `bootstrap_keys`.
+    pub fn verify(
+        &self,
+        bootstrap_keys: &[XfrPublicKey],
+        threshold: usize,
+    ) -> Result<()> {
+        if 0 == threshold || threshold > bootstrap_keys.len() {
+            return Err(eg!("invalid threshold"));
+        }
+
+        let payload = self.signing_payload().c(d!())?;
+
+        let signers = self
+            .signatures
+            .iter()
+            .filter_map(|s| {
+                let pk = wallet::public_key_from_base64(&s.pubkey).ok()?;
+                if !bootstrap_keys.contains(&pk) {
+                    return None;
+                }
+                base64::decode_config(&s.sig, base64::URL_SAFE)
+                    .ok()
+                    .and_then(|sig| XfrSignature::zei_from_bytes(&sig).ok())
+                    .and_then(|sig| pk.verify(&payload, &sig).ok())
+                    .map(|_| s.pubkey.clone())
+            })
+            .collect::<BTreeSet<_>>();
+
+        if signers.len() < threshold {
+            return Err(eg!(format!(
+                "initial validator-set is signed by {} bootstrap keys, {} needed",
+                signers.len(),
+                threshold
+            )));
+        }
+
+        Ok(())
+    }
 }
 
 /// Used for parsing config from disk.
-#[derive(Serialize, Deserialize, Eq, PartialEq)]
+#[derive(Clone, Serialize, Deserialize, Eq, PartialEq)]
 pub struct ValidatorStr {
     /// `XfrPublicKey` in base64 format
     pub id: String,
@@ -47,6 +129,7 @@ impl TryFrom<ValidatorStr> for Validator {
             td_addr: td_addr_to_bytes(&v.td_addr).c(d!())?,
             td_power: v.td_power.unwrap_or(DEFAULT_POWER),
             commission_rate: v.commission_rate.unwrap_or([1, 100]),
+            commission_rate_updated_at: 0,
             id: globutils::wallet::public_key_from_base64(&v.id).c(d!())?,
             memo: v.memo.map_or(Default::default(), |s| {
                 serde_json::from_str(s.as_str()).unwrap_or_default()
@@ -62,6 +145,7 @@ impl TryFrom<ValidatorStr> for Validator {
 /// generate the initial validator-set
 pub fn get_inital_validators() -> Result<Vec<Validator>> {
     get_cfg_data().c(d!()).and_then(|i| {
+        verify_cfg_data(&i).c(d!())?;
         i.valiators
             .into_iter()
             .map(|v| Validator::try_from(v).c(d!()))
@@ -70,6 +154,78 @@ pub fn get_inital_validators() -> Result<Vec<Validator>> {
     })
 }
 
+/// Verify the signatures of the initial validator-set.
+///
+/// The bootstrap keys are fixed at compiling time by `STAKING_GENESIS_BOOTSTRAP_KEYS`,
+/// it is a comma-separated list of base64 `XfrPublicKey`s,
+/// and `STAKING_GENESIS_SIG_THRESHOLD` defaults to the number of these keys,
+/// so every node checks against the same keys.
+///
+/// Builds without any bootstrap key, such as the ones for testing,
+/// have nothing to verify against and will accept the config as is.
+pub fn verify_cfg_data(cfg: &InitialValidatorInfo) -> Result<()> {
+    let keys = get_bootstrap_keys().c(d!())?;
+    if keys.is_empty() {
+        return Ok(());
+    }
+    cfg.verify(&keys, get_sig_threshold(keys.len()).c(d!())?)
+        .c(d!())
+}
+
+/// Check the first validator-set applied to the ledger,
+/// nobody can co-sign it, so it must be the signed one compiled in.
+pub fn check_genesis_validators(vd: &ValidatorData) -> Result<()> {
+    let keys = get_bootstrap_keys().c(d!())?;
+    if keys.is_empty() {
+        return Ok(());
+    }
+    check_genesis_validators_inner(
+        get_cfg_data().c(d!())?,
+        vd,
+        &keys,
+        get_sig_threshold(keys.len()).c(d!())?,
+    )
+    .c(d!())
+}
+
+fn check_genesis_validators_inner(
+    cfg: InitialValidatorInfo,
+    vd: &ValidatorData,
+    keys: &[XfrPublicKey],
+    threshold: usize,
+) -> Result<()> {
+    cfg.verify(keys, threshold).c(d!())?;
+
+    let vs = cfg
+        .valiators
+        .into_iter()
+        .map(|v| Validator::try_from(v).c(d!()))
+        .collect::<Result<Vec<_>>>()?;
+    let expected = ValidatorData::new(vd.height, vs).c(d!())?;
+
+    if expected.body != vd.body {
+        return Err(eg!("the genesis validator-set is not the signed one"));
+    }
+
+    Ok(())
+}
+
+fn get_bootstrap_keys() -> Result<Vec<XfrPublicKey>> {
+    option_env!("STAKING_GENESIS_BOOTSTRAP_KEYS")
+        .unwrap_or_default()
+        .split(',')
+        .map(|k| k.trim())
+        .filter(|k| !k.is_empty())
+        .map(|k| wallet::public_key_from_base64(k).c(d!()))
+        .collect()
+}
+
+fn get_sig_threshold(keys_cnt: usize) -> Result<usize> {
+    option_env!("STAKING_GENESIS_SIG_THRESHOLD")
+        .map(|t| t.parse::<usize>().c(d!()))
+        .unwrap_or(Ok(keys_cnt))
+}
+
 #[allow(missing_docs)]
 #[cfg(not(any(feature = "debug_env", feature = "abci_mock")))]
 pub fn get_cfg_data() -> Result<InitialValidatorInfo> {
@@ -109,7 +265,10 @@ pub fn get_cfg_path() -> Option<&'static str> {
 #[cfg(test)]
 #[allow(missing_docs)]
 mod test {
-    use {super::*, crate::staking::td_pubkey_to_td_addr, ruc::pnk};
+    use {
+        super::*, crate::staking::td_pubkey_to_td_addr, rand_chacha::ChaChaRng,
+        rand_core::SeedableRng, ruc::pnk,
+    };
 
     #[test]
     fn staking_tendermint_addr_conversion() {
@@ -119,4 +278,63 @@ mod test {
             assert_eq!(v.td_addr, td_pubkey_to_td_addr(&pk));
         });
     }
+
+    #[test]
+    fn staking_signed_initial_validators() {
+        let kps = (0..3)
+            .map(|_| XfrKeyPair::generate(&mut ChaChaRng::from_entropy()))
+            .collect::<Vec<_>>();
+        let pks = kps.iter().map(|kp| kp.get_pk()).collect::<Vec<_>>();
+
+        let mut cfg = pnk!(get_cfg_data());
+        assert!(cfg.verify(&pks, 1).is_err());
+
+        pnk!(cfg.sign(&kps[0]));
+        pnk!(cfg.sign(&kps[1]));
+        // sign twice with the same key
+        pnk!(cfg.sign(&kps[1]));
+        assert_eq!(2, cfg.signatures.len());
+
+        pnk!(cfg.verify(&pks, 2));
+        assert!(cfg.verify(&pks, 3).is_err());
+        assert!(cfg.verify(&pks, 0).is_err());
+        assert!(cfg.verify(&pks[2..], 1).is_err());
+
+        // any change of the validator-set invalidates the signatures
+        cfg.valiators.pop();
+        assert!(cfg.verify(&pks, 1).is_err());
+    }
+
+    #[test]
+    fn staking_genesis_validators() {
+        let kps = (0..2)
+            .map(|_| XfrKeyPair::generate(&mut ChaChaRng::from_entropy()))
+            .collect::<Vec<_>>();
+        let pks = kps.iter().map(|kp| kp.get_pk()).collect::<Vec<_>>();
+
+        let gen_vd = |cfg: &InitialValidatorInfo| {
+            let vs = cfg
+                .valiators
+                .iter()
+                .cloned()
+                .map(|v| pnk!(Validator::try_from(v)))
+                .collect::<Vec<_>>();
+            pnk!(ValidatorData::new(1, vs))
+        };
+
+        let mut cfg = pnk!(get_cfg_data());
+        let vd = gen_vd(&cfg);
+
+        // not signed
+        assert!(check_genesis_validators_inner(cfg.clone(), &vd, &pks, 1).is_err());
+
+        kps.iter().for_each(|kp| pnk!(cfg.sign(kp)));
+        pnk!(check_genesis_validators_inner(cfg.clone(), &vd, &pks, 2));
+
+        // a validator-set other than the signed one
+        let mut other = cfg.clone();
+        other.valiators.pop();
+        let other_vd = gen_vd(&other);
+        assert!(check_genesis_validators_inner(cfg, &other_vd, &pks, 2).is_err());
+    }
 }
@@ -19,7 +19,8 @@ pub mod ops;
 use {
     crate::{
         data_model::{
-            ConsensusRng, Operation, Transaction, TransferAsset, TxoRef, FRA_DECIMALS,
+            is_default, AssetTypeCode, ConsensusRng, Operation, Transaction,
+            TransferAsset, TxoRef, FRA_DECIMALS,
         },
         SNAPSHOT_ENTRIES_DIR,
     },
@@ -30,6 +31,7 @@ use {
     indexmap::IndexMap,
     lazy_static::lazy_static,
     ops::{
+        consensus_params::Data as ConsensusParamsData,
         fra_distribution::FraDistributionOps,
         mint_fra::{MintKind, MINT_AMOUNT_LIMIT},
     },
@@ -39,9 +41,11 @@ use {
     serde::{Deserialize, Serialize},
     sha2::Digest as _,
     std::{
+        cmp::min,
         collections::{BTreeMap, BTreeSet},
         convert::TryFrom,
-        env, mem,
+        env, fmt, mem,
+        result::Result as StdResult,
         sync::{
             mpsc::{channel, Receiver, Sender},
             Arc,
@@ -62,6 +66,9 @@ type DAHCP = (Arc<Mutex<Sender<DAH>>>, Arc<Mutex<Receiver<DAH>>>);
 // pk, height, <struct DelegationRwdDetail>
 type DRH = (XfrPublicKey, BlockHeight, DelegationRwdDetail);
 type DRHCP = (Arc<Mutex<Sender<DRH>>>, Arc<Mutex<Receiver<DRH>>>);
+// pk, <struct RewardRecord>
+type RRH = (XfrPublicKey, RewardRecord);
+type RRHCP = (Arc<Mutex<Sender<RRH>>>, Arc<Mutex<Receiver<RRH>>>);
 
 macro_rules! chan {
     () => {{
@@ -89,6 +96,8 @@ lazy_static! {
     pub static ref CHAN_D_AMOUNT_HIST: DAHCP = chan!();
     #[allow(missing_docs)]
     pub static ref CHAN_D_RWD_HIST: DRHCP = chan!();
+    #[allow(missing_docs)]
+    pub static ref CHAN_RWD_RECORD_HIST: RRHCP = chan!();
 }
 
 /// Reserved accounts of Findora Foundation.
@@ -166,6 +175,55 @@ pub const UNBOND_BLOCK_CNT: u64 = 3600 * 24 * 21 / BLOCK_INTERVAL;
 #[cfg(feature = "abci_mock")]
 pub const UNBOND_BLOCK_CNT: u64 = 10;
 
+/// A validator can not raise its commission rate above 50%.
+pub const COMMISSION_RATE_MAX: [u64; 2] = [1, 2];
+
+/// The max change of commission rate in a single update, 1%.
+pub const COMMISSION_RATE_MAX_CHANGE: [u64; 2] = [1, 100];
+
+/// The min interval between two commission rate changes, about 1 day.
+#[cfg(not(feature = "abci_mock"))]
+pub const COMMISSION_RATE_UPDATE_BLOCK_ITV: BlockHeight = 3600 * 24 / BLOCK_INTERVAL;
+
+/// used in test/mock env
+#[cfg(feature = "abci_mock")]
+pub const COMMISSION_RATE_UPDATE_BLOCK_ITV: BlockHeight = 10;
+
+// Heights of consensus rule changes.
+//
+// Blocks below such a height are always executed with the old rules,
+// so that a node replaying the chain from genesis gets the same app hash.
+// The mainnet values are above the mainnet tip at the time of the release
+// that introduced them, `debug_env` builds (testnets) and `abci_mock` tests
+// use low ones, so that blocks on both sides of every fork are produced.
+
+/// The height from which the limitations on commission rate changes take effect,
+/// earlier `UpdateStaker` ops are applied as-is.
+#[cfg(not(any(feature = "debug_env", feature = "abci_mock")))]
+pub const COMMISSION_RATE_UPDATE_CHECK_HEIGHT: BlockHeight = 1_600_000;
+
+/// used in testnets
+#[cfg(feature = "debug_env")]
+pub const COMMISSION_RATE_UPDATE_CHECK_HEIGHT: BlockHeight = 1000;
+
+/// used in test/mock env
+#[cfg(all(feature = "abci_mock", not(feature = "debug_env")))]
+pub const COMMISSION_RATE_UPDATE_CHECK_HEIGHT: BlockHeight = 16;
+
+/// The height from which reward, commission and penalty amounts
+/// are calculated by `checked_proportion`, earlier blocks keep the
+/// original `u64` math, which clamps or wraps big products.
+#[cfg(not(any(feature = "debug_env", feature = "abci_mock")))]
+pub const CHECKED_ARITH_CHECK_HEIGHT: BlockHeight = 1_600_000;
+
+/// used in testnets
+#[cfg(feature = "debug_env")]
+pub const CHECKED_ARITH_CHECK_HEIGHT: BlockHeight = 1000;
+
+/// used in test/mock env
+#[cfg(all(feature = "abci_mock", not(feature = "debug_env")))]
+pub const CHECKED_ARITH_CHECK_HEIGHT: BlockHeight = 16;
+
 // minimal number of validators
 pub(crate) const VALIDATORS_MIN: usize = 5;
 
@@ -217,6 +275,14 @@ pub struct Staking {
     // FRA CoinBase.
     coinbase: CoinBase,
     cr: ConsensusRng,
+    // pending and applied updates of tendermint block limits.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    cp: BTreeMap<BlockHeight, ConsensusParamsData>,
+    // whitelisted fee assets, base64 code => rate against FRA.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    fee_assets: BTreeMap<String, [u64; 2]>,
 }
 
 impl Default for Staking {
@@ -248,6 +314,8 @@ impl Staking {
             cur_height: 0,
             coinbase: CoinBase::gen(),
             cr: ConsensusRng::default(),
+            cp: BTreeMap::new(),
+            fee_assets: BTreeMap::new(),
         }
     }
 
@@ -381,6 +449,57 @@ impl Staking {
         self.vi.get_mut(&h)
     }
 
+    /// Set the consensus params that will be sent to tendermint
+    /// at the specified height.
+    #[inline(always)]
+    pub fn consensus_params_set_at_height(
+        &mut self,
+        h: BlockHeight,
+        cp: ConsensusParamsData,
+    ) -> Result<()> {
+        if self.cp.contains_key(&h) {
+            Err(eg!("already exists"))
+        } else {
+            self.cp.insert(h, cp);
+            Ok(())
+        }
+    }
+
+    /// Get the consensus params exactly on a specified height.
+    #[inline(always)]
+    pub fn consensus_params_get_at_height(
+        &self,
+        h: BlockHeight,
+    ) -> Option<&ConsensusParamsData> {
+        self.cp.get(&h)
+    }
+
+    /// Get the latest consensus params that have taken effect.
+    #[inline(always)]
+    pub fn consensus_params_get_effective(&self) -> Option<&ConsensusParamsData> {
+        self.cp
+            .range(0..=self.cur_height)
+            .rev()
+            .next()
+            .map(|(_, v)| v)
+    }
+
+    /// Add, update or remove(if `rate` is `None`) a fee asset.
+    #[inline(always)]
+    pub fn fee_asset_set(&mut self, code: AssetTypeCode, rate: Option<[u64; 2]>) {
+        if let Some(r) = rate {
+            self.fee_assets.insert(code.to_base64(), r);
+        } else {
+            self.fee_assets.remove(&code.to_base64());
+        }
+    }
+
+    /// Get all the whitelisted fee assets.
+    #[inline(always)]
+    pub fn fee_assets(&self) -> &BTreeMap<String, [u64; 2]> {
+        &self.fee_assets
+    }
+
     /// Make the validators at current height to be effective.
     #[inline(always)]
     pub fn validator_apply_current(&mut self) {
@@ -543,21 +662,26 @@ impl Staking {
     #[inline(always)]
     /// update staker
     pub fn update_staker(&mut self, new: &Validator) -> Result<()> {
+        let h = self.cur_height;
         let vd = self.validator_get_current_mut().c(d!())?;
-        let res = vd.body.values_mut().any(|v| {
-            if v.id == new.id {
-                v.memo = new.memo.clone();
-                v.commission_rate = new.commission_rate;
-                return true;
-            }
-            false
-        });
-
-        if res {
-            Ok(())
-        } else {
-            Err(eg!("Cannot update staker"))
+        let v = vd
+            .body
+            .values_mut()
+            .find(|v| v.id == new.id)
+            .c(d!("Cannot update staker"))?;
+
+        if h < COMMISSION_RATE_UPDATE_CHECK_HEIGHT {
+            v.commission_rate = new.commission_rate;
+        } else if v
+            .check_commission_rate_update(new.commission_rate, h)
+            .c(d!())?
+        {
+            v.commission_rate = new.commission_rate;
+            v.commission_rate_updated_at = h;
         }
+        v.memo = new.memo.clone();
+
+        Ok(())
     }
 
     #[inline(always)]
@@ -1157,30 +1281,39 @@ impl Staking {
             return Err(eg!());
         }
 
+        let h = self.cur_height;
+        let proportion = |am: Amount| -> Result<Amount> {
+            if h < CHECKED_ARITH_CHECK_HEIGHT {
+                Ok(am.wrapping_mul(percent[0]) / percent[1])
+            } else {
+                checked_proportion(am, percent).c(d!())
+            }
+        };
+
         // punish itself
         let am = self.delegation_get(addr).c(d!())?.amount();
-        self.governance_penalty_sub_amount(addr, am * percent[0] / percent[1])
+        self.governance_penalty_sub_amount(addr, proportion(am).c(d!())?)
             .c(d!())?;
 
         if self.addr_is_validator(addr) {
             // punish related delegators
-            let pl = || {
-                self.validator_get_current_one_by_id(addr)
-                    .unwrap()
-                    .delegators
-                    .iter()
-                    .map(|(pk, am)| (*pk, am * percent[0] / percent[1]))
-                    .collect::<Vec<_>>()
-            };
+            let pl = self
+                .validator_get_current_one_by_id(addr)
+                .c(d!())?
+                .delegators
+                .iter()
+                .map(|(pk, am)| proportion(*am).map(|p| (*pk, p)))
+                .collect::<Result<Vec<_>>>()
+                .c(d!())?;
 
-            pl().into_iter().for_each(|(pk, p_am)| {
+            pl.into_iter().for_each(|(pk, p_am)| {
                 ruc::info_omit!(self.governance_penalty_sub_amount(&pk, p_am));
             });
 
             // punish its vote power
             self.validator_get_power(addr).c(d!()).and_then(|power| {
-                self.validator_change_power(addr, power * percent[0] / percent[1], true)
-                    .c(d!())
+                proportion(power)
+                    .and_then(|p| self.validator_change_power(addr, p, true).c(d!()))
             })?;
         }
 
@@ -1220,17 +1353,16 @@ impl Staking {
             return Err(eg!("delegation has been paid"));
         } else {
             // NOTE:
-            // punish principal first
+            // punish principal first,
+            // the penalty is capped by what the delegation actually holds
             d.entries.values_mut().for_each(|v| {
-                if 0 < am {
-                    let i = *v;
-                    *v = v.saturating_sub(am);
-                    am = am.saturating_sub(i);
-                }
+                let cut = min(*v, am);
+                *v -= cut;
+                am -= cut;
             });
             // NOTE:
             // punish rewards if principal is not enough
-            d.rwd_amount = d.rwd_amount.saturating_sub(am);
+            d.rwd_amount -= min(d.rwd_amount, am);
         }
 
         Ok(())
@@ -1447,7 +1579,11 @@ impl Staking {
             Ok(am)
         })?;
 
-        *self.coinbase.distribution_plan.entry(pk).or_insert(0) += am;
+        let plan = self.coinbase.distribution_plan.entry(pk).or_insert(0);
+        *plan = plan
+            .checked_add(am)
+            .ok_or(StakingArithError::Overflow)
+            .c(d!())?;
 
         Ok(())
     }
@@ -1679,6 +1815,11 @@ pub struct Validator {
     /// % commision is the % of FRA incentives the validator will take out as a commission fee
     /// for helping FRA owners stake their tokens.
     pub(crate) commission_rate: [u64; 2],
+    /// the block height of the last change of `commission_rate`,
+    /// `0` means it has never been changed
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub(crate) commission_rate_updated_at: BlockHeight,
     /// optional descriptive information
     pub memo: StakerMemo,
     kind: ValidatorKind,
@@ -1713,6 +1854,7 @@ impl Validator {
             td_power,
             id,
             commission_rate,
+            commission_rate_updated_at: 0,
             memo,
             kind,
             signed_last_block: false,
@@ -1751,6 +1893,63 @@ impl Validator {
         self.commission_rate
     }
 
+    #[inline(always)]
+    #[allow(missing_docs)]
+    pub fn get_commission_rate_updated_at(&self) -> BlockHeight {
+        self.commission_rate_updated_at
+    }
+
+    /// Check a new commission rate proposed at height `h`,
+    /// return `false` if it is the same as the current one.
+    ///
+    /// A change is allowed at most once in `COMMISSION_RATE_UPDATE_BLOCK_ITV` blocks,
+    /// and can not exceed `COMMISSION_RATE_MAX_CHANGE`,
+    /// the rate can only be raised up to `COMMISSION_RATE_MAX`.
+    pub fn check_commission_rate_update(
+        &self,
+        new_rate: [u64; 2],
+        h: BlockHeight,
+    ) -> Result<bool> {
+        if 0 == new_rate[1] || new_rate[0] > new_rate[1] {
+            return Err(eg!("invalid commission rate"));
+        }
+
+        let old_rate = self.commission_rate;
+        let old = old_rate[0] as u128 * new_rate[1] as u128;
+        let new = new_rate[0] as u128 * old_rate[1] as u128;
+        if old == new {
+            return Ok(false);
+        }
+
+        if h < self
+            .commission_rate_updated_at
+            .saturating_add(COMMISSION_RATE_UPDATE_BLOCK_ITV)
+        {
+            return Err(eg!("commission rate can only be changed once a day"));
+        }
+
+        if new > old
+            && new_rate[0] as u128 * COMMISSION_RATE_MAX[1] as u128
+                > COMMISSION_RATE_MAX[0] as u128 * new_rate[1] as u128
+        {
+            return Err(eg!("commission rate exceeds the max rate"));
+        }
+
+        // |new - old| / (old_rate[1] * new_rate[1]) <= COMMISSION_RATE_MAX_CHANGE
+        let diff = if new > old { new - old } else { old - new };
+        if diff
+            .checked_mul(COMMISSION_RATE_MAX_CHANGE[1] as u128)
+            .c(d!("overflow"))?
+            > (old_rate[1] as u128 * new_rate[1] as u128)
+                .checked_mul(COMMISSION_RATE_MAX_CHANGE[0] as u128)
+                .c(d!("overflow"))?
+        {
+            return Err(eg!("commission rate changes too much"));
+        }
+
+        Ok(true)
+    }
+
     #[inline(always)]
     #[allow(missing_docs)]
     pub fn staking_is_basic_valid(&self) -> bool {
@@ -1794,6 +1993,89 @@ pub struct Delegation {
     pub delegation_rwd_cnt: u64,
 }
 
+/// Where a reward payout comes from.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum RewardKind {
+    /// rewards of delegations
+    Delegation,
+    /// extra rewards for proposing a block
+    Proposer,
+    /// commissions paid to a validator by its delegators
+    Commission,
+}
+
+impl RewardKind {
+    #[inline(always)]
+    #[allow(missing_docs)]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RewardKind::Delegation => "delegation",
+            RewardKind::Proposer => "proposer",
+            RewardKind::Commission => "commission",
+        }
+    }
+}
+
+/// A single reward payout of a delegator.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RewardRecord {
+    #[allow(missing_docs)]
+    pub block_height: BlockHeight,
+    #[allow(missing_docs)]
+    pub amount: Amount,
+    #[allow(missing_docs)]
+    pub kind: RewardKind,
+}
+
+/// Cumulative rewards of an address, grouped by `RewardKind`.
+#[derive(Clone, Default, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RewardTotals {
+    #[allow(missing_docs)]
+    pub delegation: Amount,
+    #[allow(missing_docs)]
+    pub proposer: Amount,
+    #[allow(missing_docs)]
+    pub commission: Amount,
+}
+
+impl RewardTotals {
+    /// Add a reward to the total of its kind,
+    /// the total is left untouched if it would overflow.
+    #[inline(always)]
+    pub fn add(&mut self, r: &RewardRecord) -> StdResult<(), StakingArithError> {
+        let total = match r.kind {
+            RewardKind::Delegation => &mut self.delegation,
+            RewardKind::Proposer => &mut self.proposer,
+            RewardKind::Commission => &mut self.commission,
+        };
+        *total = total
+            .checked_add(r.amount)
+            .ok_or(StakingArithError::Overflow)?;
+        Ok(())
+    }
+}
+
+/// Reward records older than this will be dropped, about one year.
+pub const REWARD_RECORD_RETENTION: BlockHeight = 365 * 24 * 3600 / BLOCK_INTERVAL;
+
+#[cfg(not(target_arch = "wasm32"))]
+#[inline(always)]
+pub(crate) fn send_reward_record(
+    pk: XfrPublicKey,
+    block_height: BlockHeight,
+    amount: Amount,
+    kind: RewardKind,
+) {
+    if *KEEP_HIST && 0 < amount {
+        let r = RewardRecord {
+            block_height,
+            amount,
+            kind,
+        };
+        CHAN_RWD_RECORD_HIST.0.lock().send((pk, r)).unwrap();
+    }
+}
+
 /// Detail of each reward entry.
 #[derive(Clone, Default, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct DelegationRwdDetail {
@@ -1886,10 +2168,20 @@ impl Delegation {
 
         self.validator_entry(validator)
             .c(d!())
-            .and_then(|mut am| {
+            .and_then(|am| {
                 if 0 < am {
                     // APY
-                    am += self.rwd_amount.saturating_mul(am) / self.amount();
+                    let am = if cur_height < CHECKED_ARITH_CHECK_HEIGHT {
+                        am.wrapping_add(
+                            self.rwd_amount.saturating_mul(am) / self.amount(),
+                        )
+                    } else {
+                        checked_proportion(self.rwd_amount, [am, self.amount()])
+                            .and_then(|i| {
+                                am.checked_add(i).ok_or(StakingArithError::Overflow)
+                            })
+                            .c(d!())?
+                    };
                     calculate_delegation_rewards(am, return_rate)
                         .c(d!())
                         .map(|n| alt!(n > coinbase_bl, coinbase_bl, n))
@@ -1901,8 +2193,11 @@ impl Delegation {
                 }
             })
             .and_then(|mut n| {
-                let commission =
-                    n.saturating_mul(commission_rate[0]) / commission_rate[1];
+                let commission = if cur_height < CHECKED_ARITH_CHECK_HEIGHT {
+                    n.saturating_mul(commission_rate[0]) / commission_rate[1]
+                } else {
+                    checked_proportion(n, commission_rate).c(d!())?
+                };
                 n = n.checked_sub(commission).c(d!())?;
                 if is_delegation_rwd && *KEEP_HIST {
                     let r = DelegationRwdDetail {
@@ -1920,6 +2215,16 @@ impl Delegation {
                         .send((self.id, cur_height, r))
                         .unwrap();
                 }
+                send_reward_record(
+                    self.id,
+                    cur_height,
+                    n,
+                    alt!(
+                        is_delegation_rwd,
+                        RewardKind::Delegation,
+                        RewardKind::Proposer
+                    ),
+                );
                 self.rwd_amount.checked_add(n).c(d!()).map(|i| {
                     self.rwd_amount = i;
                     commission
@@ -1948,6 +2253,40 @@ pub fn calculate_delegation_rewards(
         .and_then(|n| u64::try_from(n).c(d!()))
 }
 
+/// Arithmetic failures of the staking reward and penalty calculations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StakingArithError {
+    /// The result can not be represented as an `Amount`.
+    Overflow,
+    /// The denominator of a rate is zero.
+    ZeroDenominator,
+}
+
+impl fmt::Display for StakingArithError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StakingArithError::Overflow => write!(f, "Amount overflow"),
+            StakingArithError::ZeroDenominator => write!(f, "Zero denominator"),
+        }
+    }
+}
+
+impl std::error::Error for StakingArithError {}
+
+/// Calculate `amount * rate[0] / rate[1]` with `u128` intermediates,
+/// an overflow or a zero denominator is reported as an error
+/// instead of being wrapped or clamped silently.
+pub fn checked_proportion(
+    amount: Amount,
+    rate: [u64; 2],
+) -> StdResult<Amount, StakingArithError> {
+    if 0 == rate[1] {
+        return Err(StakingArithError::ZeroDenominator);
+    }
+    u64::try_from(amount as u128 * rate[0] as u128 / rate[1] as u128)
+        .map_err(|_| StakingArithError::Overflow)
+}
+
 #[allow(missing_docs)]
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, Eq, PartialEq)]
 pub enum DelegationState {
@@ -2141,6 +2480,151 @@ mod test {
         });
     }
 
+    #[test]
+    fn staking_consensus_params() {
+        let mut s = Staking::new();
+        let cp = ConsensusParamsData {
+            height: 10,
+            max_bytes: 2 * 1024 * 1024,
+            max_gas: -1,
+        };
+
+        pnk!(s.consensus_params_set_at_height(10, cp.clone()));
+        assert!(s.consensus_params_set_at_height(10, cp.clone()).is_err());
+
+        assert!(s.consensus_params_get_at_height(9).is_none());
+        assert_eq!(s.consensus_params_get_at_height(10), Some(&cp));
+
+        s.set_custom_block_height(9);
+        assert!(s.consensus_params_get_effective().is_none());
+        s.set_custom_block_height(11);
+        assert_eq!(s.consensus_params_get_effective(), Some(&cp));
+    }
+
+    #[test]
+    fn staking_hash_compatible_with_default_fields() {
+        // the layout of `Staking` before `cp` and `fee_assets` were added
+        #[derive(Serialize, Deserialize)]
+        struct StakingV0 {
+            vi: ValidatorInfo,
+            di: DelegationInfo,
+            cur_height: BlockHeight,
+            coinbase: CoinBase,
+            cr: ConsensusRng,
+        }
+
+        let s = Staking::new();
+        let s0 = StakingV0 {
+            vi: s.vi.clone(),
+            di: s.di.clone(),
+            cur_height: s.cur_height,
+            coinbase: s.coinbase.clone(),
+            cr: s.cr,
+        };
+
+        assert_eq!(
+            globutils::HashOf::new(&s).as_ref(),
+            globutils::HashOf::new(&s0).as_ref()
+        );
+    }
+
+    #[test]
+    fn staking_commission_rate_update() {
+        let mut v = pnk!(Validator::new_staker(
+            vec![0; 32],
+            *crate::data_model::BLACK_HOLE_PUBKEY_STAKING,
+            [10, 100],
+            Default::default(),
+        ));
+        // never changed, keep the serialized form of historical validators
+        assert!(!pnk!(serde_json::to_string(&v)).contains("commission_rate_updated_at"));
+
+        v.commission_rate_updated_at = 100;
+        let h = 100 + COMMISSION_RATE_UPDATE_BLOCK_ITV;
+
+        // unchanged, no matter the height
+        assert!(!pnk!(v.check_commission_rate_update([1, 10], 101)));
+        assert!(!pnk!(v.check_commission_rate_update([10, 100], h)));
+
+        assert!(v.check_commission_rate_update([11, 100], h - 1).is_err());
+        assert!(pnk!(v.check_commission_rate_update([11, 100], h)));
+        assert!(pnk!(v.check_commission_rate_update([9, 100], h)));
+        assert!(v.check_commission_rate_update([12, 100], h).is_err());
+        assert!(v.check_commission_rate_update([8, 100], h).is_err());
+
+        v.commission_rate = [50, 100];
+        assert!(v.check_commission_rate_update([51, 100], h).is_err());
+        v.commission_rate = [60, 100];
+        assert!(pnk!(v.check_commission_rate_update([59, 100], h)));
+
+        assert!(v.check_commission_rate_update([1, 0], h).is_err());
+        assert!(v.check_commission_rate_update([2, 1], h).is_err());
+    }
+
+    #[test]
+    fn staking_checked_proportion() {
+        assert_eq!(checked_proportion(0, [1, 1]), Ok(0));
+        assert_eq!(checked_proportion(u64::MAX, [1, 1]), Ok(u64::MAX));
+        assert_eq!(checked_proportion(u64::MAX, [0, 1]), Ok(0));
+        assert_eq!(checked_proportion(u64::MAX, [1, 2]), Ok(u64::MAX / 2));
+        assert_eq!(
+            checked_proportion(u64::MAX, [u64::MAX, u64::MAX]),
+            Ok(u64::MAX)
+        );
+        assert_eq!(
+            checked_proportion(u64::MAX - 1, [99, 100]),
+            Ok(18262276632972456097)
+        );
+        // the product exceeds `u64`, but the result does not
+        assert_eq!(
+            checked_proportion(100_000_000_000, [1_000_000_000, 2_000_000_000]),
+            Ok(50_000_000_000)
+        );
+
+        assert_eq!(
+            checked_proportion(1, [1, 0]),
+            Err(StakingArithError::ZeroDenominator)
+        );
+        assert_eq!(
+            checked_proportion(u64::MAX, [2, 1]),
+            Err(StakingArithError::Overflow)
+        );
+        assert_eq!(
+            checked_proportion(2, [u64::MAX, 1]),
+            Err(StakingArithError::Overflow)
+        );
+    }
+
+    #[test]
+    fn staking_reward_totals_overflow() {
+        let r = |amount, kind| RewardRecord {
+            block_height: 1,
+            amount,
+            kind,
+        };
+
+        let mut t = RewardTotals::default();
+        assert!(t.add(&r(u64::MAX - 1, RewardKind::Proposer)).is_ok());
+        assert!(t.add(&r(1, RewardKind::Proposer)).is_ok());
+        assert_eq!(
+            t.add(&r(1, RewardKind::Proposer)),
+            Err(StakingArithError::Overflow)
+        );
+        assert_eq!(t.proposer, u64::MAX);
+
+        // other kinds are counted separately
+        assert!(t.add(&r(1, RewardKind::Commission)).is_ok());
+        assert_eq!(t.commission, 1);
+    }
+
+    #[test]
+    fn staking_calculate_delegation_rewards_boundary() {
+        assert_eq!(pnk!(calculate_delegation_rewards(0, [1, 1])), 0);
+        assert!(calculate_delegation_rewards(u64::MAX, [u128::MAX, 1]).is_err());
+        assert!(calculate_delegation_rewards(1, [1, 0]).is_err());
+        assert!(calculate_delegation_rewards(u64::MAX, [u64::MAX as u128, 1]).is_err());
+    }
+
     fn gen_round_vote_percent(lower_bound: u64, upper_bound: u64) -> [u64; 2] {
         let itv = upper_bound - lower_bound;
         let lb = if 0 == itv {
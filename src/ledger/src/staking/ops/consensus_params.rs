@@ -0,0 +1,107 @@
+//!
+//! # Update Consensus Params
+//!
+//! propose new block limits for tendermint at a certain block height
+//! by using a multi-signature transaction,
+//! they will be sent out through `ResponseEndBlock` when that height arrives.
+//!
+//! **NOTE**: always use the same multi-signature rules as `UpdateValidator`.
+//!
+
+use {
+    crate::{
+        data_model::NoReplayToken,
+        staking::{cosig::CoSigOp, BlockHeight, Staking},
+    },
+    ruc::*,
+    serde::{Deserialize, Serialize},
+    zei::xfr::sig::{XfrKeyPair, XfrPublicKey},
+};
+
+/// The hard limit of tendermint on the size of a block, 100MB.
+pub const BLOCK_MAX_BYTES_LIMIT: i64 = 100 * 1024 * 1024;
+
+/// Used as the inner object of a `UpdateConsensusParams Operation`.
+pub type UpdateConsensusParamsOps = CoSigOp<Data>;
+
+impl UpdateConsensusParamsOps {
+    /// Check the validity of an operation by running it in a staking simulator.
+    #[inline(always)]
+    pub fn check_run(&self, staking_simulator: &mut Staking) -> Result<()> {
+        self.apply(staking_simulator).c(d!())
+    }
+
+    /// Apply new settings to the target `Staking` instance,
+    /// will fail if existing settings are found at the same height.
+    pub fn apply(&self, staking: &mut Staking) -> Result<()> {
+        self.verify(staking)
+            .c(d!())
+            .and_then(|_| self.check_context(staking).c(d!()))
+            .and_then(|_| {
+                staking
+                    .consensus_params_set_at_height(self.data.height, self.data.clone())
+                    .c(d!())
+            })
+    }
+
+    #[inline(always)]
+    fn check_context(&self, staking: &Staking) -> Result<()> {
+        if self.data.height <= staking.cur_height() {
+            return Err(eg!("height has passed"));
+        }
+
+        if 0 >= self.data.max_bytes || BLOCK_MAX_BYTES_LIMIT < self.data.max_bytes {
+            return Err(eg!("invalid max_bytes"));
+        }
+
+        // '-1' means unlimited
+        if -1 > self.data.max_gas {
+            return Err(eg!("invalid max_gas"));
+        }
+
+        Ok(())
+    }
+
+    #[inline(always)]
+    #[allow(missing_docs)]
+    pub fn get_related_pubkeys(&self) -> Vec<XfrPublicKey> {
+        self.cosigs.keys().copied().collect()
+    }
+
+    #[inline(always)]
+    #[allow(missing_docs)]
+    pub fn new(
+        kps: &[&XfrKeyPair],
+        height: BlockHeight,
+        max_bytes: i64,
+        max_gas: i64,
+        nonce: NoReplayToken,
+    ) -> Result<Self> {
+        let mut op = CoSigOp::create(Data::new(height, max_bytes, max_gas), nonce);
+        op.batch_sign(kps).c(d!()).map(|_| op)
+    }
+}
+
+/// The body of a `UpdateConsensusParams Operation`,
+/// fields correspond to the `BlockParams` of tendermint.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Data {
+    /// The height at which the new params take effect.
+    pub height: BlockHeight,
+    /// Max size of a block in bytes,
+    /// which is also the upper bound of a single transaction.
+    pub max_bytes: i64,
+    /// Max gas of a block, '-1' means unlimited.
+    pub max_gas: i64,
+}
+
+impl Data {
+    #[inline(always)]
+    fn new(height: BlockHeight, max_bytes: i64, max_gas: i64) -> Self {
+        Data {
+            height,
+            max_bytes,
+            max_gas,
+        }
+    }
+}
@@ -18,7 +18,7 @@ use {
     ed25519_dalek::Signer,
     ruc::*,
     serde::{Deserialize, Serialize},
-    std::collections::HashSet,
+    std::collections::BTreeSet,
     tendermint::{signature::Ed25519Signature, PrivateKey, PublicKey, Signature},
     zei::xfr::{
         sig::{XfrKeyPair, XfrPublicKey, XfrSignature},
@@ -245,7 +245,7 @@ fn check_delegation_context_principal(
                     .inputs
                     .iter()
                     .map(|i| i.public_key)
-                    .collect::<HashSet<_>>()
+                    .collect::<BTreeSet<_>>()
                     .len();
 
                 // make sure:
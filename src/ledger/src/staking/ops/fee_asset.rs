@@ -0,0 +1,92 @@
+//!
+//! # Fee Asset
+//!
+//! whitelist non-FRA assets that can be used to pay transaction fees,
+//! the exchange rate against FRA is set by a multi-signature transaction.
+//!
+//! A fee paid in a whitelisted asset is burned to `BLACK_HOLE_PUBKEY`
+//! in the same way as FRA fees, the rate is only used to check
+//! whether the burned amount is worth the minimal fee,
+//! no FRA is minted or credited for it.
+//!
+//! **NOTE**: always use the same multi-signature rules as `UpdateValidator`.
+//!
+
+use {
+    crate::{
+        data_model::{AssetTypeCode, NoReplayToken, ASSET_TYPE_FRA},
+        staking::{cosig::CoSigOp, Staking},
+    },
+    ruc::*,
+    serde::{Deserialize, Serialize},
+    zei::xfr::sig::{XfrKeyPair, XfrPublicKey},
+};
+
+/// Used as the inner object of a `UpdateFeeAsset Operation`.
+pub type UpdateFeeAssetOps = CoSigOp<Data>;
+
+impl UpdateFeeAssetOps {
+    /// Check the validity of an operation by running it in a staking simulator.
+    #[inline(always)]
+    pub fn check_run(&self, staking_simulator: &mut Staking) -> Result<()> {
+        self.apply(staking_simulator).c(d!())
+    }
+
+    /// Apply new settings to the target `Staking` instance.
+    pub fn apply(&self, staking: &mut Staking) -> Result<()> {
+        self.verify(staking)
+            .c(d!())
+            .and_then(|_| self.check_context().c(d!()))
+            .map(|_| staking.fee_asset_set(self.data.code, self.data.rate))
+    }
+
+    #[inline(always)]
+    fn check_context(&self) -> Result<()> {
+        if ASSET_TYPE_FRA == self.data.code.val {
+            return Err(eg!("FRA can not be a fee asset"));
+        }
+
+        if let Some(rate) = self.data.rate {
+            if 0 == rate[0] || 0 == rate[1] {
+                return Err(eg!("invalid rate"));
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline(always)]
+    #[allow(missing_docs)]
+    pub fn get_related_pubkeys(&self) -> Vec<XfrPublicKey> {
+        self.cosigs.keys().copied().collect()
+    }
+
+    #[inline(always)]
+    #[allow(missing_docs)]
+    pub fn new(
+        kps: &[&XfrKeyPair],
+        code: AssetTypeCode,
+        rate: Option<[u64; 2]>,
+        nonce: NoReplayToken,
+    ) -> Result<Self> {
+        let mut op = CoSigOp::create(Data::new(code, rate), nonce);
+        op.batch_sign(kps).c(d!()).map(|_| op)
+    }
+}
+
+/// The body of a `UpdateFeeAsset Operation`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Data {
+    /// The asset to be whitelisted or removed.
+    pub code: AssetTypeCode,
+    /// `[x, y]` means `x` units of the asset are worth `y` units of FRA,
+    /// `None` means removing the asset from the whitelist.
+    pub rate: Option<[u64; 2]>,
+}
+
+impl Data {
+    #[inline(always)]
+    fn new(code: AssetTypeCode, rate: Option<[u64; 2]>) -> Self {
+        Data { code, rate }
+    }
+}
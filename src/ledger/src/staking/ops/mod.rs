@@ -8,7 +8,9 @@
 //!
 
 pub mod claim;
+pub mod consensus_params;
 pub mod delegation;
+pub mod fee_asset;
 pub mod fra_distribution;
 pub mod governance;
 pub mod mint_fra;
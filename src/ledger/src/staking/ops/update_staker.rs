@@ -3,6 +3,10 @@
 //!
 //! Data representation required when users want to update information of a staker.
 //!
+//! **NOTE**: changes of the commission rate are limited,
+//! see `Validator::check_commission_rate_update` for details,
+//! they take effect from `COMMISSION_RATE_UPDATE_CHECK_HEIGHT`.
+//!
 
 use {
     crate::{
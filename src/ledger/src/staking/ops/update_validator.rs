@@ -9,7 +9,7 @@ use {
     crate::{
         data_model::NoReplayToken,
         staking::{
-            cosig::CoSigOp, BlockHeight, Staking, Validator, ValidatorData,
+            cosig::CoSigOp, init, BlockHeight, Staking, Validator, ValidatorData,
             COSIG_THRESHOLD_DEFAULT, VALIDATORS_MIN,
         },
     },
@@ -33,6 +33,7 @@ impl UpdateValidatorOps {
         self.verify(staking)
             .c(d!())
             .and_then(|_| self.check_context().c(d!()))
+            .and_then(|_| self.check_genesis(staking).c(d!()))
             .and_then(|_| {
                 staking
                     .validator_set_at_height(self.data.height, self.data.clone())
@@ -47,6 +48,7 @@ impl UpdateValidatorOps {
         self.verify(staking)
             .c(d!())
             .and_then(|_| self.check_context().c(d!()))
+            .and_then(|_| self.check_genesis(staking).c(d!()))
             .map(|_| staking.validator_set_at_height_force(self.data.height, self.data))
     }
 
@@ -67,6 +69,21 @@ impl UpdateValidatorOps {
         Ok(())
     }
 
+    // The first validator-set is not co-signed by anyone,
+    // so it is checked against the signed initial config.
+    #[inline(always)]
+    fn check_genesis(&self, staking: &Staking) -> Result<()> {
+        if staking
+            .validator_get_current()
+            .map(|vd| vd.body.is_empty())
+            .unwrap_or(true)
+        {
+            init::check_genesis_validators(&self.data).c(d!())
+        } else {
+            Ok(())
+        }
+    }
+
     #[inline(always)]
     #[allow(missing_docs)]
     pub fn get_related_pubkeys(&self) -> Vec<XfrPublicKey> {
@@ -10,15 +10,16 @@ use {
         },
         staking::{
             ops::mint_fra::MintEntry, Amount, BlockHeight, DelegationRwdDetail,
-            CHAN_D_AMOUNT_HIST, CHAN_D_RWD_HIST, CHAN_GLOB_RATE_HIST,
-            CHAN_V_SELF_D_HIST,
+            RewardRecord, RewardTotals, CHAN_D_AMOUNT_HIST, CHAN_D_RWD_HIST,
+            CHAN_GLOB_RATE_HIST, CHAN_RWD_RECORD_HIST, CHAN_V_SELF_D_HIST,
+            REWARD_RECORD_RETENTION,
         },
     },
     fbnc::{new_mapx, new_mapxnk, Mapx, Mapxnk},
     globutils::wallet,
     ruc::*,
     serde::{Deserialize, Serialize},
-    std::collections::HashSet,
+    std::collections::BTreeSet,
     zei::xfr::{sig::XfrPublicKey, structs::OwnerMemo},
 };
 
@@ -28,9 +29,9 @@ type Issuances = Vec<(TxOutput, Option<OwnerMemo>)>;
 #[derive(Clone, Deserialize, Serialize)]
 pub struct ApiCache {
     /// Set of transactions related to a ledger address
-    pub related_transactions: Mapx<XfrAddress, HashSet<TxnSID>>,
+    pub related_transactions: Mapx<XfrAddress, BTreeSet<TxnSID>>,
     /// Set of transfer transactions related to an asset code
-    pub related_transfers: Mapx<AssetTypeCode, HashSet<TxnSID>>,
+    pub related_transfers: Mapx<AssetTypeCode, BTreeSet<TxnSID>>,
     /// List of claim transactions related to a ledger address
     pub claim_hist_txns: Mapx<XfrAddress, Vec<TxnSID>>,
     /// Payments from coinbase
@@ -63,6 +64,16 @@ pub struct ApiCache {
     /// rewards history, used on some pulic nodes, such as fullnode
     pub staking_delegation_rwd_hist:
         Mapx<XfrPublicKey, Mapxnk<BlockHeight, DelegationRwdDetail>>,
+    /// - every reward payout of a delegator, used for tax reports
+    /// - only the recent `REWARD_RECORD_RETENTION` blocks are kept
+    pub staking_rwd_records: Mapx<XfrPublicKey, Mapxnk<BlockHeight, Vec<RewardRecord>>>,
+    /// addresses that got reward records at each height,
+    /// used to prune `staking_rwd_records` once per block
+    pub staking_rwd_record_heights: Mapxnk<BlockHeight, Vec<XfrPublicKey>>,
+    /// - cumulative rewards of every address, never dropped
+    /// - only complete if `KEEP_HIST` has been enabled since genesis,
+    ///   otherwise rewards paid before it was enabled are missing
+    pub staking_rwd_totals: Mapx<XfrPublicKey, RewardTotals>,
 }
 
 impl ApiCache {
@@ -111,6 +122,18 @@ impl ApiCache {
                 "api_cache/{}staking_delegation_rwd_hist",
                 prefix
             )),
+            staking_rwd_records: new_mapx!(format!(
+                "api_cache/{}staking_rwd_records",
+                prefix
+            )),
+            staking_rwd_record_heights: new_mapxnk!(format!(
+                "api_cache/{}staking_rwd_record_heights",
+                prefix
+            )),
+            staking_rwd_totals: new_mapx!(format!(
+                "api_cache/{}staking_rwd_totals",
+                prefix
+            )),
         }
     }
 
@@ -148,7 +171,7 @@ impl ApiCache {
     }
 
     /// Cache history style data
-    pub fn cache_hist_data(&mut self) {
+    pub fn cache_hist_data(&mut self, cur_height: BlockHeight) {
         CHAN_GLOB_RATE_HIST.1.lock().try_iter().for_each(|(h, r)| {
             self.staking_global_rate_hist.insert(h, r);
         });
@@ -207,6 +230,56 @@ impl ApiCache {
                 dd.global_delegation_percent = r.global_delegation_percent
             );
         });
+
+        CHAN_RWD_RECORD_HIST
+            .1
+            .lock()
+            .try_iter()
+            .for_each(|(pk, r)| {
+                ruc::info_omit!(self
+                    .staking_rwd_totals
+                    .entry(pk)
+                    .or_insert_with(RewardTotals::default)
+                    .add(&r)
+                    .c(d!()));
+
+                #[allow(unused_mut)]
+                let mut hist =
+                    self.staking_rwd_records
+                        .entry(pk)
+                        .or_insert(new_mapxnk!(format!(
+                            "staking_rwd_records_subdata/{}",
+                            wallet::public_key_to_base64(&pk)
+                        )));
+
+                let h = r.block_height;
+                #[allow(unused_mut)]
+                let mut records = hist.entry(h).or_insert_with(Vec::new);
+                if records.is_empty() {
+                    self.staking_rwd_record_heights
+                        .entry(h)
+                        .or_insert_with(Vec::new)
+                        .push(pk);
+                }
+                records.push(r);
+            });
+
+        // drop reward records out of the retention window
+        if let Some(expired) = cur_height.checked_sub(REWARD_RECORD_RETENTION) {
+            while let Some((h, pks)) = self
+                .staking_rwd_record_heights
+                .get_closest_smaller(&expired)
+            {
+                let pks = pks.to_vec();
+                pks.iter().for_each(|pk| {
+                    #[allow(unused_mut)]
+                    if let Some(mut hist) = self.staking_rwd_records.get_mut(pk) {
+                        hist.remove(&h);
+                    }
+                });
+                self.staking_rwd_record_heights.remove(&h);
+            }
+        }
     }
 }
 
@@ -219,11 +292,11 @@ impl ApiCache {
 pub fn get_related_addresses<F>(
     txn: &Transaction,
     mut classify: F,
-) -> HashSet<XfrAddress>
+) -> BTreeSet<XfrAddress>
 where
     F: FnMut(&Operation),
 {
-    let mut related_addresses = HashSet::new();
+    let mut related_addresses = BTreeSet::new();
 
     macro_rules! staking_gen {
         ($op: expr) => {{
@@ -244,6 +317,8 @@ where
             Operation::Governance(i) => staking_gen!(i),
             Operation::FraDistribution(i) => staking_gen!(i),
             Operation::MintFra(i) => staking_gen!(i),
+            Operation::UpdateConsensusParams(i) => staking_gen!(i),
+            Operation::UpdateFeeAsset(i) => staking_gen!(i),
             Operation::TransferAsset(transfer) => {
                 for input in transfer.body.transfer.inputs.iter() {
                     related_addresses.insert(XfrAddress {
@@ -280,8 +355,8 @@ where
 /// Returns the set of nonconfidential assets transferred in a transaction.
 pub fn get_transferred_nonconfidential_assets(
     txn: &Transaction,
-) -> HashSet<AssetTypeCode> {
-    let mut transferred_assets = HashSet::new();
+) -> BTreeSet<AssetTypeCode> {
+    let mut transferred_assets = BTreeSet::new();
     for op in &txn.body.operations {
         if let Operation::TransferAsset(transfer) = op {
             for input in transfer.body.transfer.inputs.iter() {
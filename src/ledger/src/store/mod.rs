@@ -14,13 +14,14 @@ use {
         data_model::{
             AssetType, AssetTypeCode, AuthenticatedBlock, AuthenticatedTransaction,
             AuthenticatedUtxo, AuthenticatedUtxoStatus, BlockEffect, BlockSID,
-            FinalizedBlock, FinalizedTransaction, IssuerKeyPair, IssuerPublicKey,
-            Operation, OutputPosition, StateCommitmentData, Transaction, TransferType,
-            TxnEffect, TxnSID, TxnTempSID, TxoSID, UnAuthenticatedUtxo, Utxo,
-            UtxoStatus, XfrAddress, BLACK_HOLE_PUBKEY,
+            BlockWriteSet, FinalizedBlock, FinalizedTransaction, IssuerKeyPair,
+            IssuerPublicKey, Operation, OutputPosition, StateCommitmentData,
+            Transaction, TransferType, TxnEffect, TxnSID, TxnTempSID, TxoSID,
+            UnAuthenticatedUtxo, Utxo, UtxoStatus, XfrAddress, BLACK_HOLE_PUBKEY,
         },
         staking::{
-            Amount, Power, Staking, TendermintAddrRef, FF_PK_LIST, FRA_TOTAL_AMOUNT,
+            send_reward_record, Amount, Power, RewardKind, Staking, StakingArithError,
+            TendermintAddrRef, CHECKED_ARITH_CHECK_HEIGHT, FF_PK_LIST, FRA_TOTAL_AMOUNT,
             KEEP_HIST,
         },
         LSSED_VAR, SNAPSHOT_ENTRIES_DIR,
@@ -30,6 +31,7 @@ use {
     cryptohash::sha256::Digest as BitDigest,
     fbnc::{new_mapx, new_mapxnk, new_vecx, Mapx, Mapxnk, Vecx},
     globutils::{HashOf, ProofOf},
+    lazy_static::lazy_static,
     merkle_tree::AppendOnlyMerkle,
     parking_lot::RwLock,
     rand_chacha::ChaChaRng,
@@ -38,7 +40,7 @@ use {
     serde::{Deserialize, Serialize},
     sliding_set::SlidingSet,
     std::{
-        collections::{BTreeMap, HashMap, HashSet},
+        collections::{BTreeMap, BTreeSet},
         env,
         fs::{self, OpenOptions},
         io::ErrorKind,
@@ -55,7 +57,13 @@ use {
 
 const TRANSACTION_WINDOW_WIDTH: u64 = 128;
 
-type TmpSidMap = HashMap<TxnTempSID, (TxnSID, Vec<TxoSID>)>;
+lazy_static! {
+    /// will be set in `findorad` together with '--determinism-audit' option,
+    /// record the hash of the write set of every block.
+    pub static ref DETERMINISM_AUDIT: bool = env::var("FINDORAD_DETERMINISM_AUDIT").is_ok();
+}
+
+type TmpSidMap = BTreeMap<TxnTempSID, (TxnSID, Vec<TxoSID>)>;
 
 /// findora ledger
 #[derive(Clone)]
@@ -73,6 +81,8 @@ pub struct LedgerState {
 
     // current block effect (middle cache)
     block_ctx: Option<BlockEffect>,
+    // write set of the last finished block, only recorded with `DETERMINISM_AUDIT`
+    write_set_hash: Option<HashOf<BlockWriteSet>>,
 
     // Merkle tree tracing the sequence of transaction hashes in the block
     // Each appended hash is the hash of transactions in the same block
@@ -126,6 +136,23 @@ impl LedgerState {
             })
     }
 
+    /// Run all the checks of `check_tx` and `apply_transaction`
+    /// against a copy of current state, nothing will be changed.
+    ///
+    /// **NOTE**: transactions cached in the open block are not considered.
+    pub fn simulate_transaction(&self, tx: Transaction) -> Result<()> {
+        if !tx.valid_in_abci_with_fee_assets(self.get_staking().fee_assets()) {
+            return Err(eg!("insufficient fee or coinbase transaction"));
+        }
+
+        let te = TxnEffect::compute_effect(tx).c(d!())?;
+        self.status.check_txn_effects(&te).c(d!())?;
+
+        let mut block = BlockEffect::default();
+        *block.get_staking_simulator_mut() = self.get_staking().clone();
+        block.add_txn_effect(te).c(d!()).map(|_| ())
+    }
+
     // Update the UTXO bitmap
     fn update_utxo_map(
         &mut self,
@@ -245,7 +272,8 @@ impl LedgerState {
             return Ok(());
         }
 
-        self.api_cache.cache_hist_data();
+        let cur_height = self.get_staking().cur_height();
+        self.api_cache.cache_hist_data(cur_height);
 
         let block = if let Some(b) = self.blocks.last() {
             b
@@ -318,7 +346,7 @@ impl LedgerState {
                 self.api_cache
                     .related_transactions
                     .entry(*address)
-                    .or_insert_with(HashSet::new)
+                    .or_insert_with(BTreeSet::new)
                     .insert(txn_sid);
             }
 
@@ -329,7 +357,7 @@ impl LedgerState {
                 self.api_cache
                     .related_transfers
                     .entry(*asset)
-                    .or_insert_with(HashSet::new)
+                    .or_insert_with(BTreeSet::new)
                     .insert(txn_sid);
             }
 
@@ -346,18 +374,21 @@ impl LedgerState {
                 };
             }
 
+            // Index every transaction by its tendermint hash,
+            // including those that create no new utxos
+            let hash = curr_txn.hash_tm().hex().to_uppercase();
+            self.api_cache.txn_sid_to_hash.insert(txn_sid, hash.clone());
+            self.api_cache.txn_hash_to_sid.insert(hash.clone(), txn_sid);
+
             // Add new utxos (this handles both transfers and issuances)
             for (txo_sid, (address, owner_memo)) in txo_sids
                 .iter()
                 .zip(addresses.iter().zip(owner_memos.iter()))
             {
                 self.api_cache.utxos_to_map_index.insert(*txo_sid, *address);
-                let hash = curr_txn.hash_tm().hex().to_uppercase();
                 self.api_cache
                     .txo_to_txnid
                     .insert(*txo_sid, (txn_sid, hash.clone()));
-                self.api_cache.txn_sid_to_hash.insert(txn_sid, hash.clone());
-                self.api_cache.txn_hash_to_sid.insert(hash.clone(), txn_sid);
                 if let Some(owner_memo) = owner_memo {
                     self.api_cache
                         .owner_memos
@@ -374,6 +405,10 @@ impl LedgerState {
     ///    Apply current block to ledger status
     ///    Update Utxo map
     pub fn finish_block(&mut self, mut block: BlockEffect) -> Result<TmpSidMap> {
+        if *DETERMINISM_AUDIT {
+            self.write_set_hash = Some(block.compute_write_set_hash());
+        }
+
         {
             let mut utxo_map = self.utxo_map.write();
             for (inp_sid, _) in block.input_txos.iter() {
@@ -389,6 +424,14 @@ impl LedgerState {
             .map(|_| tsm)
     }
 
+    /// Take the write set hash of the last finished block,
+    /// `None` if no block has been finished since the last call,
+    /// or `DETERMINISM_AUDIT` is off.
+    #[inline(always)]
+    pub fn take_write_set_hash(&mut self) -> Option<HashOf<BlockWriteSet>> {
+        self.write_set_hash.take()
+    }
+
     #[inline(always)]
     #[allow(missing_docs)]
     pub fn get_staking_mut(&mut self) -> &mut Staking {
@@ -538,6 +581,7 @@ impl LedgerState {
                 LedgerState::init_utxo_map(&utxo_map_path).c(d!())?,
             )),
             block_ctx: Some(BlockEffect::default()),
+            write_set_hash: None,
             api_cache: ApiCache::new(&prefix),
         };
 
@@ -624,8 +668,28 @@ impl LedgerState {
             .collect::<Result<Vec<_>>>()
             .c(d!())?;
 
-        if let Some(v) = self.get_staking_mut().delegation_get_mut(&pk) {
-            v.rwd_amount = v.rwd_amount.saturating_add(commissions.into_iter().sum());
+        if h < CHECKED_ARITH_CHECK_HEIGHT {
+            let commission = commissions
+                .into_iter()
+                .fold(0, |acc: Amount, c| acc.wrapping_add(c));
+            if let Some(v) = self.get_staking_mut().delegation_get_mut(&pk) {
+                v.rwd_amount = v.rwd_amount.saturating_add(commission);
+                send_reward_record(pk, h, commission, RewardKind::Commission);
+            }
+        } else {
+            let commission: Amount = commissions
+                .into_iter()
+                .try_fold(0, |acc: Amount, c| acc.checked_add(c))
+                .ok_or(StakingArithError::Overflow)
+                .c(d!())?;
+            if let Some(v) = self.get_staking_mut().delegation_get_mut(&pk) {
+                v.rwd_amount = v
+                    .rwd_amount
+                    .checked_add(commission)
+                    .ok_or(StakingArithError::Overflow)
+                    .c(d!())?;
+                send_reward_record(pk, h, commission, RewardKind::Commission);
+            }
         }
 
         if let Some(vote_percent) = block_vote_percent {
@@ -1003,7 +1067,7 @@ pub struct LedgerStatus {
     pub snapshot_file: String,
     utxos: Mapxnk<TxoSID, Utxo>, // all currently-unspent TXOs
     nonconfidential_balances: Mapx<XfrPublicKey, u64>,
-    owned_utxos: Mapx<XfrPublicKey, HashSet<TxoSID>>,
+    owned_utxos: Mapx<XfrPublicKey, BTreeSet<TxoSID>>,
     /// all spent TXOs
     pub spent_utxos: Mapxnk<TxoSID, Utxo>,
     // Map a TXO to its output position in a transaction
@@ -1035,7 +1099,7 @@ pub struct LedgerStatus {
     td_commit_height: u64,
 
     // An obsolete feature, ignore it!
-    tracing_policies: HashMap<AssetTypeCode, TracingPolicy>,
+    tracing_policies: BTreeMap<AssetTypeCode, TracingPolicy>,
 }
 
 impl LedgerStatus {
@@ -1134,7 +1198,7 @@ impl LedgerStatus {
             issuance_amounts: new_mapx!(issuance_amounts_path.as_str()),
             state_commitment_versions: new_vecx!(state_commitment_versions_path.as_str()),
             asset_types: new_mapx!(asset_types_path.as_str()),
-            tracing_policies: map! {},
+            tracing_policies: map! {B},
             issuance_num: new_mapx!(issuance_num_path.as_str()),
             next_txn: TxnSID(0),
             next_txo: TxoSID(0),
@@ -1375,7 +1439,7 @@ impl LedgerStatus {
         block.no_replay_tokens.clear();
 
         // Remove consumed UTXOs
-        for (inp_sid, utxo) in block.input_txos.drain() {
+        for (inp_sid, utxo) in mem::take(&mut block.input_txos) {
             if let Some(mut v) = self.owned_utxos.get_mut(&utxo.record.public_key) {
                 v.deref_mut().remove(&inp_sid);
             }
@@ -1392,12 +1456,12 @@ impl LedgerStatus {
         }
 
         // Apply memo updates
-        for (code, memo) in block.memo_updates.drain() {
+        for (code, memo) in mem::take(&mut block.memo_updates) {
             let mut asset = self.asset_types.get_mut(&code).unwrap();
             (*asset).properties.memo = memo;
         }
 
-        for (code, amount) in block.issuance_amounts.drain() {
+        for (code, amount) in mem::take(&mut block.issuance_amounts) {
             let mut amt = self.issuance_amounts.entry(code).or_insert(0);
             *amt.deref_mut() += amount;
         }
@@ -1406,7 +1470,7 @@ impl LedgerStatus {
         // Each transaction gets a TxnSID, and each of its unspent TXOs gets
         // a TxoSID. TxoSID assignments are based on the order TXOs appear in
         // the transaction.
-        let mut new_utxo_sids: HashMap<TxnTempSID, (TxnSID, Vec<TxoSID>)> = map! {};
+        let mut new_utxo_sids: BTreeMap<TxnTempSID, (TxnSID, Vec<TxoSID>)> = map! {B};
         {
             let mut next_txn = self.next_txn.0;
             let mut next_txo = self.next_txo.0;
@@ -1423,7 +1487,7 @@ impl LedgerStatus {
                     if let Some(tx_output) = txo {
                         self.owned_utxos
                             .entry(tx_output.record.public_key)
-                            .or_insert_with(HashSet::new)
+                            .or_insert_with(BTreeSet::new)
                             .insert(TxoSID(txo_sid));
                         let utxo = Utxo(tx_output);
                         #[allow(unused_mut)]
@@ -1445,14 +1509,14 @@ impl LedgerStatus {
         }
 
         // Update issuance sequence number limits
-        for (code, seq_nums) in block.new_issuance_nums.drain() {
+        for (code, seq_nums) in mem::take(&mut block.new_issuance_nums) {
             // One more than the greatest sequence number, or 0
             let new_max_seq_num = seq_nums.last().map(|x| x + 1).unwrap_or(0);
             self.issuance_num.insert(code, new_max_seq_num);
         }
 
         // Register new asset types
-        for (code, asset_type) in block.new_asset_codes.drain() {
+        for (code, asset_type) in mem::take(&mut block.new_asset_codes) {
             self.asset_types.insert(code, asset_type.clone());
         }
 
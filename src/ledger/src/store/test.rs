@@ -22,10 +22,10 @@ use {
 };
 
 #[cfg(test)]
-fn abort_block(block: BlockEffect) -> HashMap<TxnTempSID, Transaction> {
+fn abort_block(block: BlockEffect) -> BTreeMap<TxnTempSID, Transaction> {
     let mut block = block;
     let txns = block.txns.drain(..);
-    let ret: HashMap<TxnTempSID, Transaction> =
+    let ret: BTreeMap<TxnTempSID, Transaction> =
         block.temp_sids.drain(..).zip(txns).collect();
 
     block.txos.clear();
@@ -787,3 +787,43 @@ fn test_check_fee_with_ledger() {
     let mut block = ledger.start_block().unwrap();
     assert!(ledger.apply_transaction(&mut block, effect).is_err());
 }
+
+#[test]
+fn test_simulate_transaction() {
+    let mut ledger = LedgerState::tmp_ledger();
+    let mut prng = ChaChaRng::from_entropy();
+    let key_pair = XfrKeyPair::generate(&mut prng);
+
+    // an asset other than FRA can not be defined without fee
+    let code = AssetTypeCode::gen_random();
+    let tx = create_definition_transaction(
+        &code,
+        &key_pair,
+        AssetRules::default(),
+        None,
+        ledger.get_block_commit_count(),
+    )
+    .unwrap();
+    assert!(ledger.simulate_transaction(tx).is_err());
+
+    // defining FRA need no fee
+    let fra_code = AssetTypeCode {
+        val: ASSET_TYPE_FRA,
+    };
+    let tx = create_definition_transaction(
+        &fra_code,
+        &key_pair,
+        AssetRules::default(),
+        None,
+        ledger.get_block_commit_count(),
+    )
+    .unwrap();
+    pnk!(ledger.simulate_transaction(tx.clone()));
+    assert!(ledger.get_asset_type(&fra_code).is_none());
+
+    apply_transaction(&mut ledger, tx.clone());
+    assert!(ledger.get_asset_type(&fra_code).is_some());
+
+    // replayed
+    assert!(ledger.simulate_transaction(tx).is_err());
+}
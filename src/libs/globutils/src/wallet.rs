@@ -8,13 +8,121 @@ use {
     bech32::{self, FromBase32, ToBase32},
     bip0039::{Count, Language, Mnemonic},
     ed25519_dalek_bip32::{DerivationPath, ExtendedSecretKey},
+    hmac::{Hmac, Mac, NewMac},
+    primitive_types::H160,
+    ripemd160::Ripemd160,
     ruc::*,
+    secp256k1::{PublicKey as SecpPublicKey, SecretKey as SecpSecretKey},
+    sha2::{Sha256, Sha512},
+    sha3::{Digest, Keccak256},
+    std::{fmt, result::Result as StdResult},
+    subtle::ConstantTimeEq,
+    tiny_hderive::bip32::ExtendedPrivKey,
+    unicode_normalization::UnicodeNormalization,
     zei::{
         serialization::ZeiFromToBytes,
-        xfr::sig::{XfrKeyPair, XfrPublicKey, XfrSecretKey},
+        xfr::sig::{XfrKeyPair, XfrPublicKey, XfrSecretKey, XfrSignature},
     },
+    zeroize::Zeroize,
 };
 
+/// Secret key material, such as seeds and raw secret keys.
+///
+/// The inner value is wiped from memory on drop,
+/// and the comparison between two secrets is done in constant time.
+pub struct Secret<T: Zeroize + AsRef<[u8]>>(T);
+
+impl<T: Zeroize + AsRef<[u8]>> Secret<T> {
+    #[inline(always)]
+    #[allow(missing_docs)]
+    pub fn new(inner: T) -> Self {
+        Secret(inner)
+    }
+
+    /// Borrow the raw secret, avoid copying it out.
+    #[inline(always)]
+    pub fn expose(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Zeroize + AsRef<[u8]>> Drop for Secret<T> {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl<T: Zeroize + AsRef<[u8]>> PartialEq for Secret<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_ref().ct_eq(other.0.as_ref()).into()
+    }
+}
+
+impl<T: Zeroize + AsRef<[u8]>> Eq for Secret<T> {}
+
+impl<T: Zeroize + AsRef<[u8]>> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secret(***)")
+    }
+}
+
+// parse the mnemonic and generate the seed of it.
+#[inline(always)]
+fn mnemonic_to_seed(phrase: &str, lang: &str) -> Result<Secret<[u8; 64]>> {
+    resolve_mnemonic_lang(phrase, lang)
+        .c(d!())
+        .and_then(|l| parse_mnemonic(phrase, l).map_err(|e| eg!(e)))
+        .map(|m| Secret::new(m.to_seed("")))
+}
+
+// check the `lang`, or detect it from the phrase if it is `LANG_AUTO`.
+#[inline(always)]
+fn resolve_mnemonic_lang(phrase: &str, lang: &str) -> Result<Language> {
+    alt!(LANG_AUTO == lang, detect_mnemonic_lang(phrase), Ok(lang))
+        .and_then(check_lang)
+        .c(d!())
+}
+
+// The same phrase may reach us in different unicode forms,
+// e.g. Japanese/Korean words typed on some mobile keyboards,
+// so try the BIP-39 canonical NFKD form first, then the composed NFC form.
+// Words are re-joined with an ASCII space, which is what NFKD does
+// to the ideographic space used by Japanese phrases as well.
+fn parse_mnemonic(phrase: &str, l: Language) -> StdResult<Mnemonic, bip0039::Error> {
+    let words = phrase.split_whitespace().collect::<Vec<_>>().join(" ");
+    let nfkd = words.nfkd().collect::<String>();
+    let nfc = words.nfc().collect::<String>();
+
+    Mnemonic::from_phrase_in(l, nfkd.as_str()).or_else(|e| {
+        if nfc == nfkd {
+            Err(e)
+        } else {
+            Mnemonic::from_phrase_in(l, nfc.as_str())
+        }
+    })
+}
+
+/// Pass this as the `lang` of the restore functions
+/// to detect the language of the mnemonic automatically.
+pub const LANG_AUTO: &str = "auto";
+
+const SUPPORTED_LANGS: [&str; 8] =
+    ["en", "zh", "zh_traditional", "fr", "it", "ko", "sp", "jp"];
+
+/// Detect the language of a mnemonic,
+/// the first supported language in which the phrase is valid is returned.
+pub fn detect_mnemonic_lang(phrase: &str) -> Result<&'static str> {
+    SUPPORTED_LANGS
+        .iter()
+        .copied()
+        .find(|lang| {
+            check_lang(lang)
+                .map(|l| parse_mnemonic(phrase, l).is_ok())
+                .unwrap_or(false)
+        })
+        .c(d!("unable to detect the language of the mnemonic"))
+}
+
 /// Randomly generate a 12words-length mnemonic.
 #[inline(always)]
 pub fn generate_mnemonic_default() -> String {
@@ -44,27 +152,103 @@ pub fn generate_mnemonic_custom(wordslen: u8, lang: &str) -> Result<String> {
     Ok(Mnemonic::generate_in(l, w).into_phrase())
 }
 
+/// Reasons why a mnemonic is not acceptable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MnemonicError {
+    /// The language is not one of the supported ones.
+    UnsupportedLanguage(String),
+    /// Only 12/15/18/21/24 words can be accepted, the actual count is attached.
+    BadWordCount(usize),
+    /// Some word is not in the wordlist of the language.
+    InvalidWord,
+    /// All words are valid, but the checksum does not match.
+    InvalidChecksum,
+}
+
+impl fmt::Display for MnemonicError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MnemonicError::UnsupportedLanguage(l) => {
+                write!(f, "Unsupported language: {}", l)
+            }
+            MnemonicError::BadWordCount(n) => write!(
+                f,
+                "Invalid words length: {}, only 12/15/18/21/24 can be accepted",
+                n
+            ),
+            MnemonicError::InvalidWord => write!(f, "Unknown word in mnemonic"),
+            MnemonicError::InvalidChecksum => write!(f, "Invalid mnemonic checksum"),
+        }
+    }
+}
+
+impl std::error::Error for MnemonicError {}
+
+/// Check whether a mnemonic is valid without deriving any key from it,
+/// `lang` can be [LANG_AUTO](self::LANG_AUTO).
+pub fn validate_mnemonic(phrase: &str, lang: &str) -> StdResult<(), MnemonicError> {
+    let l = if LANG_AUTO == lang {
+        None
+    } else {
+        Some(
+            check_lang(lang)
+                .map_err(|_| MnemonicError::UnsupportedLanguage(lang.to_owned()))?,
+        )
+    };
+
+    let cnt = phrase.split_whitespace().count();
+    if ![12, 15, 18, 21, 24].contains(&cnt) {
+        return Err(MnemonicError::BadWordCount(cnt));
+    }
+
+    match l {
+        Some(l) => parse_mnemonic(phrase, l)
+            .map(|_| ())
+            .map_err(mnemonic_error),
+        // no language accepts the phrase, report a checksum error
+        // if all of its words belong to one of them
+        None => detect_mnemonic_lang(phrase).map(|_| ()).map_err(|_| {
+            if SUPPORTED_LANGS.iter().any(|l| {
+                validate_mnemonic(phrase, l) == Err(MnemonicError::InvalidChecksum)
+            }) {
+                MnemonicError::InvalidChecksum
+            } else {
+                MnemonicError::InvalidWord
+            }
+        }),
+    }
+}
+
+#[allow(unreachable_patterns)]
+fn mnemonic_error(e: bip0039::Error) -> MnemonicError {
+    match e {
+        bip0039::Error::BadWordCount(n) => MnemonicError::BadWordCount(n),
+        bip0039::Error::UnknownWord(_) => MnemonicError::InvalidWord,
+        _ => MnemonicError::InvalidChecksum,
+    }
+}
+
 // do the real restore operation.
 macro_rules! restore_keypair_from_mnemonic {
     ($phrase: expr, $l: expr, $p: expr, $bip: tt) => {
-        check_lang($l)
+        mnemonic_to_seed($phrase, $l)
             .c(d!())
-            .and_then(|l| Mnemonic::from_phrase_in(l, $phrase).map_err(|e| eg!(e)))
-            .map(|m| m.to_seed(""))
             .and_then(|seed| {
                 DerivationPath::$bip($p.coin, $p.account, $p.change, $p.address)
                     .map_err(|e| eg!(e))
                     .map(|dp| (seed, dp))
             })
             .and_then(|(seed, dp)| {
-                ExtendedSecretKey::from_seed(&seed)
+                ExtendedSecretKey::from_seed(seed.expose())
                     .map_err(|e| eg!(e))?
                     .derive(&dp)
                     .map_err(|e| eg!(e))
             })
             .and_then(|kp| {
-                XfrSecretKey::zei_from_bytes(&kp.secret_key.to_bytes()[..])
-                    .map_err(|e| eg!(e))
+                XfrSecretKey::zei_from_bytes(
+                    &Secret::new(kp.secret_key.to_bytes()).expose()[..],
+                )
+                .map_err(|e| eg!(e))
             })
             .map(|sk| sk.into_keypair())
     };
@@ -113,7 +297,7 @@ pub fn restore_keypair_from_seckey_base64(sec: &str) -> Result<XfrKeyPair> {
 }
 
 /// Restore the XfrKeyPair from a mnemonic with custom params,
-/// in bip44 form.
+/// in bip44 form, `lang` can be [LANG_AUTO](self::LANG_AUTO).
 #[inline(always)]
 pub fn restore_keypair_from_mnemonic_bip44(
     phrase: &str,
@@ -133,7 +317,7 @@ fn restore_keypair_from_mnemonic_bip44_inner(
 }
 
 /// Restore the XfrKeyPair from a mnemonic with custom params,
-/// in bip49 form.
+/// in bip49 form, `lang` can be [LANG_AUTO](self::LANG_AUTO).
 #[inline(always)]
 pub fn restore_keypair_from_mnemonic_bip49(
     phrase: &str,
@@ -152,6 +336,507 @@ fn restore_keypair_from_mnemonic_bip49_inner(
     restore_keypair_from_mnemonic!(phrase, lang, path, bip49).c(d!())
 }
 
+/// Derive `count` XfrKeyPairs in bip44 form, with the address index starting
+/// from `start_index`, the `address` field of `path_prefix` is ignored.
+///
+/// The mnemonic is parsed and the master key is generated only once,
+/// so this is much faster than calling `restore_keypair_from_mnemonic_bip44` in a loop.
+pub fn derive_keypairs_from_mnemonic(
+    phrase: &str,
+    lang: &str,
+    path_prefix: &BipPath,
+    start_index: u32,
+    count: u32,
+) -> Result<Vec<(BipPath, XfrKeyPair)>> {
+    let end_index = start_index
+        .checked_add(count)
+        .c(d!("address index overflow"))?;
+
+    let seed = mnemonic_to_seed(phrase, lang).c(d!())?;
+    let master = ExtendedSecretKey::from_seed(seed.expose()).map_err(|e| eg!(e))?;
+
+    (start_index..end_index)
+        .map(|idx| {
+            let path = BipPath::new(
+                path_prefix.coin,
+                path_prefix.account,
+                path_prefix.change,
+                idx,
+            );
+            DerivationPath::bip44(path.coin, path.account, path.change, path.address)
+                .map_err(|e| eg!(e))
+                .and_then(|dp| master.derive(&dp).map_err(|e| eg!(e)))
+                .and_then(|kp| {
+                    XfrSecretKey::zei_from_bytes(
+                        &Secret::new(kp.secret_key.to_bytes()).expose()[..],
+                    )
+                    .map_err(|e| eg!(e))
+                })
+                .map(|sk| (path, sk.into_keypair()))
+        })
+        .collect()
+}
+
+/// The ed25519 consensus key of a tendermint validator.
+pub struct TendermintKey {
+    /// tendermint address, in upper-hex format
+    pub address: String,
+    /// raw bytes of the public key
+    pub pub_key: [u8; 32],
+    priv_key: Secret<[u8; 32]>,
+}
+
+impl TendermintKey {
+    /// Render in the format of tendermint's `priv_validator_key.json`.
+    pub fn to_priv_validator_key_json(&self) -> String {
+        let mut full_priv_key = Secret::new([0; 64]);
+        full_priv_key.0[..32].copy_from_slice(self.priv_key.expose());
+        full_priv_key.0[32..].copy_from_slice(&self.pub_key);
+
+        serde_json::json!({
+            "address": self.address,
+            "pub_key": {
+                "type": "tendermint/PubKeyEd25519",
+                "value": base64::encode(&self.pub_key),
+            },
+            "priv_key": {
+                "type": "tendermint/PrivKeyEd25519",
+                "value": base64::encode(&full_priv_key.expose()[..]),
+            },
+        })
+        .to_string()
+    }
+}
+
+/// The `change` field of the path used to derive tendermint consensus keys,
+/// that is "m/44'/917'/account'/2/0", `0` and `1` are reserved by bip44.
+const TENDERMINT_KEY_CHANGE: u32 = 2;
+
+/// Derive the tendermint consensus key of a validator from its staking mnemonic,
+/// so that one backup can cover both keys.
+pub fn derive_tendermint_key_from_mnemonic(
+    phrase: &str,
+    lang: &str,
+    account: u32,
+) -> Result<TendermintKey> {
+    const FRA: u32 = 917;
+
+    let kp = restore_keypair_from_mnemonic!(
+        phrase,
+        lang,
+        BipPath::new(FRA, account, TENDERMINT_KEY_CHANGE, 0),
+        bip44
+    )
+    .c(d!())?;
+
+    let mut pub_key = [0; 32];
+    pub_key.copy_from_slice(&kp.get_pk_ref().zei_to_bytes());
+    let mut priv_key = Secret::new([0; 32]);
+    priv_key
+        .0
+        .copy_from_slice(&Secret::new(kp.get_sk_ref().zei_to_bytes()).expose()[..]);
+
+    Ok(TendermintKey {
+        address: hex::encode_upper(&Sha256::digest(&pub_key)[..20]),
+        pub_key,
+        priv_key,
+    })
+}
+
+/////////////////////////////////////////////////////////////////
+
+/// Split the entropy of a mnemonic into SLIP-39 shares.
+/// - @param `group_threshold`: how many groups are required to restore
+/// - @param `groups`: `(member_threshold, member_count)` of each group
+/// - @param `passphrase`: used to encrypt the master secret, can be empty
+///
+/// The returned shares are organized by group, each share is a phrase,
+/// `lang` can be [LANG_AUTO](self::LANG_AUTO).
+pub fn split_mnemonic_to_slip39_shares(
+    phrase: &str,
+    lang: &str,
+    group_threshold: u8,
+    groups: &[(u8, u8)],
+    passphrase: &str,
+) -> Result<Vec<Vec<String>>> {
+    const ITERATION_EXPONENT: u8 = 1;
+
+    let l = resolve_mnemonic_lang(phrase, lang).c(d!())?;
+    let m = parse_mnemonic(phrase, l).map_err(|e| eg!(e))?;
+
+    sssmc39::generate_mnemonics(
+        group_threshold,
+        groups,
+        m.entropy(),
+        passphrase,
+        ITERATION_EXPONENT,
+    )
+    .map_err(|e| eg!(e))?
+    .iter()
+    .map(|g| {
+        g.mnemonic_list()
+            .map_err(|e| eg!(e))
+            .map(|shares| shares.into_iter().map(|words| words.join(" ")).collect())
+    })
+    .collect()
+}
+
+/// Recover the original mnemonic from a threshold of SLIP-39 shares,
+/// the `lang` should be the same as the one used when splitting.
+pub fn restore_mnemonic_from_slip39_shares(
+    shares: &[String],
+    passphrase: &str,
+    lang: &str,
+) -> Result<String> {
+    let l = check_lang(lang).c(d!())?;
+    let shares = shares
+        .iter()
+        .map(|s| s.split_whitespace().map(|w| w.to_owned()).collect())
+        .collect::<Vec<Vec<String>>>();
+
+    let secret = Secret::new(
+        sssmc39::combine_mnemonics(&shares, passphrase).map_err(|e| eg!(e))?,
+    );
+
+    Mnemonic::from_entropy_in(l, &secret.expose()[..])
+        .map_err(|e| eg!(e))
+        .map(|m| m.into_phrase())
+}
+
+/// Restore the XfrKeyPair from a threshold of SLIP-39 shares,
+/// in bip44 form.
+pub fn restore_keypair_from_slip39_shares(
+    shares: &[String],
+    passphrase: &str,
+    lang: &str,
+    path: &BipPath,
+) -> Result<XfrKeyPair> {
+    restore_mnemonic_from_slip39_shares(shares, passphrase, lang)
+        .c(d!())
+        .and_then(|phrase| {
+            restore_keypair_from_mnemonic_bip44_inner(&phrase, lang, path).c(d!())
+        })
+}
+
+/////////////////////////////////////////////////////////////////
+
+/// A secp256k1 keypair, used on the EVM side of the chain.
+pub struct SecpPair {
+    secret: Secret<[u8; 32]>,
+    public: SecpPublicKey,
+}
+
+impl SecpPair {
+    /// Raw bytes of the secret key.
+    #[inline(always)]
+    pub fn secret_bytes(&self) -> Secret<[u8; 32]> {
+        Secret::new(*self.secret.expose())
+    }
+
+    /// Uncompressed public key, without the leading `0x04` tag.
+    #[inline(always)]
+    pub fn public_bytes(&self) -> [u8; 64] {
+        let mut pk = [0; 64];
+        pk.copy_from_slice(&self.public.serialize()[1..]);
+        pk
+    }
+
+    /// The EVM address, that is the last 20 bytes of `keccak256(public_bytes)`.
+    #[inline(always)]
+    pub fn address(&self) -> H160 {
+        Self::pubkey_to_address(&self.public)
+    }
+
+    #[inline(always)]
+    fn pubkey_to_address(pk: &SecpPublicKey) -> H160 {
+        H160::from_slice(&Keccak256::digest(&pk.serialize()[1..])[12..])
+    }
+}
+
+/// Restore the SecpPair from a mnemonic with the default ethereum bip44-path,
+/// that is "m/44'/60'/0'/0/0" ("m/44'/coin'/account'/change/address").
+#[inline(always)]
+pub fn restore_secp_keypair_from_mnemonic_default(phrase: &str) -> Result<SecpPair> {
+    const ETH: u32 = 60;
+    restore_secp_keypair_from_mnemonic_bip44(phrase, "en", &BipPath::new(ETH, 0, 0, 0))
+        .c(d!())
+}
+
+/// Restore the SecpPair from a mnemonic with custom params,
+/// in bip44 form, the `coin` of the path is usually `60`.
+pub fn restore_secp_keypair_from_mnemonic_bip44(
+    phrase: &str,
+    lang: &str,
+    path: &BipPath,
+) -> Result<SecpPair> {
+    let seed = mnemonic_to_seed(phrase, lang).c(d!())?;
+
+    let dp = format!(
+        "m/44'/{}'/{}'/{}/{}",
+        path.coin, path.account, path.change, path.address
+    );
+    let secret = ExtendedPrivKey::derive(&seed.expose()[..], dp.as_str())
+        .map_err(|e| eg!(format!("{:?}", e)))
+        .map(|k| Secret::new(k.secret()))?;
+    let public = parse_secp_secret_key(&secret)
+        .c(d!())
+        .map(|sk| SecpPublicKey::from_secret_key(&sk))?;
+
+    Ok(SecpPair { secret, public })
+}
+
+// The returned key can not be wiped,
+// so keep it as short-lived as possible.
+#[inline(always)]
+fn parse_secp_secret_key(raw: &Secret<[u8; 32]>) -> Result<SecpSecretKey> {
+    SecpSecretKey::parse(raw.expose()).map_err(|e| eg!(format!("{:?}", e)))
+}
+
+/// An account-level secp256k1 extended public key, as defined in BIP-32,
+/// it can derive child addresses without any secret material.
+///
+/// NOTE: the ed25519 keys of the UTXO side are derived by SLIP-10,
+/// which only supports hardened derivation, so there is no watch-only
+/// counterpart for `XfrPublicKey`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExtendedPubKey {
+    depth: u8,
+    parent_fingerprint: [u8; 4],
+    child_number: u32,
+    public: SecpPublicKey,
+    chain_code: [u8; 32],
+}
+
+const HARDENED_OFFSET: u32 = 1 << 31;
+
+// version bytes of a mainnet `xpub`
+const XPUB_VERSION: [u8; 4] = [0x04, 0x88, 0xb2, 0x1e];
+
+// version(4) | depth(1) | parent fingerprint(4) | child number(4) | chain code(32) | public key(33)
+const XPUB_LEN: usize = 78;
+
+// The first 4 bytes of `ripemd160(sha256(compressed public key))`.
+#[inline(always)]
+fn key_fingerprint(pk: &SecpPublicKey) -> [u8; 4] {
+    let mut fp = [0; 4];
+    fp.copy_from_slice(
+        &Ripemd160::digest(&Sha256::digest(&pk.serialize_compressed()))[..4],
+    );
+    fp
+}
+
+// CKD function of BIP-32, returns the (tweak, chain_code) pair.
+#[inline(always)]
+fn bip32_ckd(
+    chain_code: &[u8],
+    data: &[u8],
+) -> Result<(Secret<[u8; 32]>, Secret<[u8; 32]>)> {
+    let mut mac = Hmac::<Sha512>::new_varkey(chain_code).map_err(|e| eg!(e))?;
+    mac.update(data);
+    let i = Secret::new(mac.finalize().into_bytes().to_vec());
+
+    let mut il = Secret::new([0; 32]);
+    il.0.copy_from_slice(&i.expose()[..32]);
+    let mut ir = Secret::new([0; 32]);
+    ir.0.copy_from_slice(&i.expose()[32..]);
+
+    // a tweak out of the curve order is invalid
+    parse_secp_secret_key(&il).c(d!())?;
+
+    Ok((il, ir))
+}
+
+impl ExtendedPubKey {
+    /// Derive a non-hardened child key.
+    pub fn derive_child(&self, index: u32) -> Result<Self> {
+        if index >= HARDENED_OFFSET {
+            return Err(eg!("Hardened child can not be derived from a public key"));
+        }
+
+        let mut data = self.public.serialize_compressed().to_vec();
+        data.extend_from_slice(&index.to_be_bytes());
+        let (tweak, chain_code) = bip32_ckd(&self.chain_code, &data).c(d!())?;
+
+        let mut public = self.public;
+        public
+            .tweak_add_assign(&parse_secp_secret_key(&tweak).c(d!())?)
+            .map_err(|e| eg!(format!("{:?}", e)))?;
+
+        Ok(ExtendedPubKey {
+            depth: self.depth.checked_add(1).c(d!("Path too deep"))?,
+            parent_fingerprint: key_fingerprint(&self.public),
+            child_number: index,
+            public,
+            chain_code: *chain_code.expose(),
+        })
+    }
+
+    /// Derive the EVM address at "change/address" under this account.
+    #[inline(always)]
+    pub fn derive_address(&self, change: u32, address: u32) -> Result<H160> {
+        self.derive_child(change)
+            .c(d!())
+            .and_then(|k| k.derive_child(address).c(d!()))
+            .map(|k| SecpPair::pubkey_to_address(&k.public))
+    }
+
+    /// Serialize to the standard Base58Check `xpub` form of BIP-32.
+    pub fn to_base58(&self) -> String {
+        let mut bytes = Vec::with_capacity(XPUB_LEN);
+        bytes.extend_from_slice(&XPUB_VERSION);
+        bytes.push(self.depth);
+        bytes.extend_from_slice(&self.parent_fingerprint);
+        bytes.extend_from_slice(&self.child_number.to_be_bytes());
+        bytes.extend_from_slice(&self.chain_code);
+        bytes.extend_from_slice(&self.public.serialize_compressed());
+        bs58::encode(bytes).with_check().into_string()
+    }
+
+    /// Restore from a Base58Check `xpub`, such as the result of `to_base58`.
+    pub fn from_base58(xpub: &str) -> Result<Self> {
+        let bytes = bs58::decode(xpub)
+            .with_check(None)
+            .into_vec()
+            .map_err(|e| eg!(e))?;
+        if XPUB_LEN != bytes.len() {
+            return Err(eg!("Invalid length of extended public key"));
+        }
+        if XPUB_VERSION[..] != bytes[..4] {
+            return Err(eg!("Not a mainnet xpub"));
+        }
+
+        let mut parent_fingerprint = [0; 4];
+        parent_fingerprint.copy_from_slice(&bytes[5..9]);
+        let mut child_number = [0; 4];
+        child_number.copy_from_slice(&bytes[9..13]);
+        let mut chain_code = [0; 32];
+        chain_code.copy_from_slice(&bytes[13..45]);
+        let mut pk = [0; 33];
+        pk.copy_from_slice(&bytes[45..]);
+
+        SecpPublicKey::parse_compressed(&pk)
+            .map_err(|e| eg!(format!("{:?}", e)))
+            .map(|public| ExtendedPubKey {
+                depth: bytes[4],
+                parent_fingerprint,
+                child_number: u32::from_be_bytes(child_number),
+                public,
+                chain_code,
+            })
+    }
+}
+
+/// Export the extended public key at the account level of a bip44 path,
+/// that is "m/44'/coin'/account'", the `coin` is usually `60`.
+#[inline(always)]
+pub fn export_xpub_from_mnemonic(
+    phrase: &str,
+    lang: &str,
+    coin: u32,
+    account: u32,
+) -> Result<ExtendedPubKey> {
+    let seed = mnemonic_to_seed(phrase, lang).c(d!())?;
+    export_xpub_from_seed(&seed.expose()[..], &[44, coin, account]).c(d!())
+}
+
+// Derive along a path of hardened indexes, such as `[44, 60, 0]` for "m/44'/60'/0'".
+fn export_xpub_from_seed(seed: &[u8], path: &[u32]) -> Result<ExtendedPubKey> {
+    let (mut sk, mut chain_code) = bip32_ckd(b"Bitcoin seed", seed).c(d!())?;
+    let mut parent_fingerprint = [0; 4];
+    let mut child_number = 0;
+
+    for idx in path.iter() {
+        let idx = idx.checked_add(HARDENED_OFFSET).c(d!("Invalid path"))?;
+
+        let mut data = Secret::new(vec![0]);
+        data.0.extend_from_slice(sk.expose());
+        data.0.extend_from_slice(&idx.to_be_bytes());
+        let (tweak, cc) = bip32_ckd(chain_code.expose(), data.expose()).c(d!())?;
+
+        let mut child = parse_secp_secret_key(&sk).c(d!())?;
+        parent_fingerprint = key_fingerprint(&SecpPublicKey::from_secret_key(&child));
+        child
+            .tweak_add_assign(&parse_secp_secret_key(&tweak).c(d!())?)
+            .map_err(|e| eg!(format!("{:?}", e)))?;
+        sk = Secret::new(child.serialize());
+        chain_code = cc;
+        child_number = idx;
+    }
+
+    Ok(ExtendedPubKey {
+        depth: u8::try_from(path.len()).c(d!("Path too deep"))?,
+        parent_fingerprint,
+        child_number,
+        public: parse_secp_secret_key(&sk)
+            .c(d!())
+            .map(|sk| SecpPublicKey::from_secret_key(&sk))?,
+        chain_code: *chain_code.expose(),
+    })
+}
+
+/// Convert a SecpPair to its EVM address, in EIP-55 checksummed form.
+#[inline(always)]
+pub fn secp_keypair_to_address(kp: &SecpPair) -> String {
+    evm_address_to_checksum(&kp.address())
+}
+
+/// Compute the address of a contract deployed by `CREATE2`,
+/// that is `keccak256(0xff ++ deployer ++ salt ++ keccak256(init_code))[12..]`.
+pub fn create2_address(
+    deployer: &H160,
+    salt: &[u8; 32],
+    init_code_hash: &[u8; 32],
+) -> H160 {
+    let mut hasher = Keccak256::new();
+    hasher.update(&[0xff]);
+    hasher.update(deployer.as_bytes());
+    hasher.update(salt);
+    hasher.update(init_code_hash);
+    H160::from_slice(&hasher.finalize()[12..])
+}
+
+/// Encode an EVM address in the EIP-55 mixed-case checksum form,
+/// eg. "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed".
+pub fn evm_address_to_checksum(addr: &H160) -> String {
+    let lower = hex::encode(addr.as_bytes());
+    let hash = Keccak256::digest(lower.as_bytes());
+
+    let checksummed = lower
+        .char_indices()
+        .map(|(i, c)| {
+            let nibble = (hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0x0f;
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect::<String>();
+
+    format!("0x{}", checksummed)
+}
+
+/// Parse an EVM address, both the all-lowercase/all-uppercase form
+/// and the EIP-55 checksummed form are accepted,
+/// a mixed-case address with a wrong checksum will be rejected.
+pub fn evm_address_from_str(addr: &str) -> Result<H160> {
+    let s = addr.strip_prefix("0x").unwrap_or(addr);
+    if 40 != s.len() {
+        return Err(eg!("Invalid length of EVM address"));
+    }
+
+    let bytes = hex::decode(s).c(d!())?;
+    let res = H160::from_slice(&bytes);
+
+    let is_mixed_case = s.chars().any(|c| c.is_ascii_lowercase())
+        && s.chars().any(|c| c.is_ascii_uppercase());
+    if is_mixed_case && evm_address_to_checksum(&res)[2..] != *s {
+        return Err(eg!("Invalid EIP-55 checksum"));
+    }
+
+    Ok(res)
+}
+
 // check and generate a Language object from its string value.
 #[inline(always)]
 fn check_lang(lang: &str) -> Result<Language> {
@@ -212,6 +897,66 @@ fn bech32dec(input: &str) -> Result<Vec<u8>> {
 
 /////////////////////////////////////////////////////////////////
 
+// prepended to every message before signing,
+// so that a signed message can never be a valid transaction.
+const SIGNED_MESSAGE_PREFIX: &[u8] = b"\x19Findora Signed Message:\n";
+
+#[inline(always)]
+fn signed_message_payload(msg: &[u8]) -> Vec<u8> {
+    let mut payload = SIGNED_MESSAGE_PREFIX.to_vec();
+    payload.extend_from_slice(msg.len().to_string().as_bytes());
+    payload.extend_from_slice(msg);
+    payload
+}
+
+/// Sign an arbitrary message for off-chain authentication,
+/// the message is prefixed with "\x19Findora Signed Message:\n" and its length.
+#[inline(always)]
+pub fn sign_message(kp: &XfrKeyPair, msg: &[u8]) -> XfrSignature {
+    kp.get_sk_ref()
+        .sign(&signed_message_payload(msg), kp.get_pk_ref())
+}
+
+/// Verify a signature produced by `sign_message`.
+#[inline(always)]
+pub fn verify_message(pk: &XfrPublicKey, msg: &[u8], sig: &XfrSignature) -> Result<()> {
+    pk.verify(&signed_message_payload(msg), sig).c(d!())
+}
+
+/// Build the message to sign for proving the ownership of an address,
+/// the `nonce` is issued by a node and can be used only once.
+#[inline(always)]
+pub fn ownership_challenge_message(address: &str, nonce: &str) -> String {
+    format!(
+        "Findora address ownership proof\naddress: {}\nnonce: {}",
+        address, nonce
+    )
+}
+
+/// Sign an ownership challenge, return a `XfrSignature` in base64 format.
+pub fn sign_ownership_challenge(kp: &XfrKeyPair, nonce: &str) -> String {
+    let msg = ownership_challenge_message(&public_key_to_base64(kp.get_pk_ref()), nonce);
+    base64::encode_config(
+        &sign_message(kp, msg.as_bytes()).zei_to_bytes(),
+        base64::URL_SAFE,
+    )
+}
+
+/// Verify a signature produced by `sign_ownership_challenge`.
+pub fn verify_ownership_challenge(
+    pk: &XfrPublicKey,
+    nonce: &str,
+    sig: &str,
+) -> Result<()> {
+    let msg = ownership_challenge_message(&public_key_to_base64(pk), nonce);
+    base64::decode_config(sig, base64::URL_SAFE)
+        .c(d!())
+        .and_then(|sig| XfrSignature::zei_from_bytes(&sig).c(d!()))
+        .and_then(|sig| verify_message(pk, msg.as_bytes(), &sig).c(d!()))
+}
+
+/////////////////////////////////////////////////////////////////
+
 #[cfg(test)]
 #[allow(missing_docs)]
 mod test {
@@ -242,6 +987,41 @@ mod test {
             });
     }
 
+    #[test]
+    fn t_detect_mnemonic_lang() {
+        let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        assert_eq!("en", pnk!(detect_mnemonic_lang(phrase)));
+        assert!(detect_mnemonic_lang("findora findora findora").is_err());
+
+        SUPPORTED_LANGS.iter().for_each(|lang| {
+            let phrase = pnk!(generate_mnemonic_custom(12, lang));
+            let path = BipPath::new(917, 0, 0, 0);
+            let kp = pnk!(restore_keypair_from_mnemonic_bip44(&phrase, lang, &path));
+
+            // extra and non-ascii whitespaces, decomposed or composed characters
+            let variants = [
+                format!(
+                    "  {}  ",
+                    phrase.split(' ').collect::<Vec<_>>().join("\u{3000}")
+                ),
+                phrase.nfkd().collect::<String>(),
+                phrase.nfc().collect::<String>(),
+            ];
+            variants.iter().for_each(|p| {
+                assert_eq!(
+                    kp.get_pk(),
+                    pnk!(restore_keypair_from_mnemonic_bip44(p, lang, &path)).get_pk()
+                );
+                assert_eq!(Ok(()), validate_mnemonic(p, lang));
+                assert_eq!(Ok(()), validate_mnemonic(p, LANG_AUTO));
+            });
+
+            pnk!(restore_keypair_from_mnemonic_bip44(
+                &phrase, LANG_AUTO, &path
+            ));
+        });
+    }
+
     #[test]
     fn t_generate_mnemonic_bad() {
         assert!(generate_mnemonic_custom(12, "xx").is_err());
@@ -254,6 +1034,309 @@ mod test {
         XfrKeyPair::generate(&mut small_rng)
     }
 
+    #[test]
+    fn t_validate_mnemonic() {
+        let phrase = generate_mnemonic_default();
+        assert_eq!(Ok(()), validate_mnemonic(&phrase, "en"));
+
+        assert_eq!(
+            Err(MnemonicError::UnsupportedLanguage("xx".to_owned())),
+            validate_mnemonic(&phrase, "xx")
+        );
+
+        let words = phrase.split(' ').collect::<Vec<_>>();
+        assert_eq!(
+            Err(MnemonicError::BadWordCount(11)),
+            validate_mnemonic(&words[1..].join(" "), "en")
+        );
+
+        let mut bad_word = words.clone();
+        bad_word[3] = "findora";
+        assert_eq!(
+            Err(MnemonicError::InvalidWord),
+            validate_mnemonic(&bad_word.join(" "), "en")
+        );
+
+        // "abandon" * 12 is a valid-word but bad-checksum phrase
+        assert_eq!(
+            Err(MnemonicError::InvalidChecksum),
+            validate_mnemonic(&["abandon"; 12].join(" "), "en")
+        );
+
+        assert_eq!(Ok(()), validate_mnemonic(&phrase, LANG_AUTO));
+        assert_eq!(
+            Err(MnemonicError::InvalidWord),
+            validate_mnemonic(&bad_word.join(" "), LANG_AUTO)
+        );
+        assert_eq!(
+            Err(MnemonicError::InvalidChecksum),
+            validate_mnemonic(&["abandon"; 12].join(" "), LANG_AUTO)
+        );
+    }
+
+    #[test]
+    fn t_derive_keypairs() {
+        let phrase = generate_mnemonic_default();
+        let prefix = BipPath::new(917, 1, 0, 0);
+
+        let kps = pnk!(derive_keypairs_from_mnemonic(&phrase, "en", &prefix, 5, 3));
+        assert_eq!(3, kps.len());
+        kps.iter().enumerate().for_each(|(i, (path, kp))| {
+            assert_eq!(5 + i as u32, path.address);
+            let expected = pnk!(restore_keypair_from_mnemonic_bip44_inner(
+                &phrase, "en", path
+            ));
+            assert_eq!(expected.get_pk(), kp.get_pk());
+        });
+
+        assert!(
+            derive_keypairs_from_mnemonic(&phrase, "en", &prefix, u32::MAX, 2).is_err()
+        );
+    }
+
+    #[test]
+    fn t_slip39_shares() {
+        let phrase = pnk!(generate_mnemonic_custom(24, "en"));
+        let path = BipPath::new(917, 0, 0, 0);
+
+        let groups = pnk!(split_mnemonic_to_slip39_shares(
+            &phrase,
+            "en",
+            1,
+            &[(2, 3)],
+            "pass"
+        ));
+        assert_eq!(1, groups.len());
+        assert_eq!(3, groups[0].len());
+
+        let shares = vec![groups[0][0].clone(), groups[0][2].clone()];
+        assert_eq!(
+            phrase,
+            pnk!(restore_mnemonic_from_slip39_shares(&shares, "pass", "en"))
+        );
+        assert_eq!(
+            pnk!(restore_keypair_from_mnemonic_bip44_inner(
+                &phrase, "en", &path
+            ))
+            .get_pk(),
+            pnk!(restore_keypair_from_slip39_shares(
+                &shares, "pass", "en", &path
+            ))
+            .get_pk()
+        );
+
+        assert!(
+            restore_mnemonic_from_slip39_shares(&shares[..1], "pass", "en").is_err()
+        );
+
+        // the phrase is normalized and its language detected,
+        // as what the restore functions do
+        let messy = format!("  {}\n", phrase.replace(' ', "\u{3000}"));
+        let groups = pnk!(split_mnemonic_to_slip39_shares(
+            &messy,
+            LANG_AUTO,
+            1,
+            &[(2, 3)],
+            "pass"
+        ));
+        let shares = vec![groups[0][1].clone(), groups[0][2].clone()];
+        assert_eq!(
+            phrase,
+            pnk!(restore_mnemonic_from_slip39_shares(&shares, "pass", "en"))
+        );
+    }
+
+    #[test]
+    fn t_restore_secp_keypair() {
+        let phrase = "test test test test test test test test test test test junk";
+        let kp = pnk!(restore_secp_keypair_from_mnemonic_default(phrase));
+        assert_eq!(
+            "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266",
+            secp_keypair_to_address(&kp)
+        );
+
+        let kp1 = pnk!(restore_secp_keypair_from_mnemonic_bip44(
+            phrase,
+            "en",
+            &BipPath::new(60, 0, 0, 1)
+        ));
+        assert_eq!(
+            "0x70997970C51812dc3A010C7d01b50e0d17dc79C8",
+            secp_keypair_to_address(&kp1)
+        );
+
+        assert!(restore_secp_keypair_from_mnemonic_default("bad phrase").is_err());
+    }
+
+    #[test]
+    fn t_watch_only_xpub() {
+        let phrase = generate_mnemonic_default();
+        let xpub = pnk!(export_xpub_from_mnemonic(&phrase, "en", 60, 2));
+        assert!(xpub.to_base58().starts_with("xpub"));
+        let xpub = pnk!(ExtendedPubKey::from_base58(&xpub.to_base58()));
+
+        (0..3).for_each(|i| {
+            let kp = pnk!(restore_secp_keypair_from_mnemonic_bip44(
+                &phrase,
+                "en",
+                &BipPath::new(60, 2, 0, i)
+            ));
+            assert_eq!(kp.address(), pnk!(xpub.derive_address(0, i)));
+        });
+
+        assert!(xpub.derive_child(HARDENED_OFFSET).is_err());
+        assert!(ExtendedPubKey::from_base58("AAAA").is_err());
+    }
+
+    #[test]
+    fn t_xpub_bip32_test_vector() {
+        // test vector 1 of BIP-32
+        let seed = pnk!(hex::decode("000102030405060708090a0b0c0d0e0f"));
+
+        let master = pnk!(export_xpub_from_seed(&seed, &[]));
+        assert_eq!(
+            "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8",
+            master.to_base58()
+        );
+
+        // m/0'
+        let child = pnk!(export_xpub_from_seed(&seed, &[0]));
+        assert_eq!(
+            "xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw",
+            child.to_base58()
+        );
+        assert_eq!(child, pnk!(ExtendedPubKey::from_base58(&child.to_base58())));
+
+        // the checksum must be verified
+        let mut bad = child.to_base58();
+        bad.pop();
+        bad.push('x');
+        assert!(ExtendedPubKey::from_base58(&bad).is_err());
+    }
+
+    #[test]
+    fn t_evm_address_checksum() {
+        [
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+            "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+            "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+        ]
+        .iter()
+        .for_each(|addr| {
+            let h = pnk!(evm_address_from_str(addr));
+            assert_eq!(*addr, evm_address_to_checksum(&h));
+            assert_eq!(h, pnk!(evm_address_from_str(&addr.to_lowercase())));
+            assert_eq!(h, pnk!(evm_address_from_str(&addr[2..].to_uppercase())));
+        });
+
+        assert!(
+            evm_address_from_str("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD").is_err()
+        );
+        assert!(
+            evm_address_from_str("0x5aaeb6053f3e94c9b9a09f33669435e7ef1bea").is_err()
+        );
+    }
+
+    #[test]
+    fn t_create2_address() {
+        // example 0 and example 5 of EIP-1014
+        let hash = |code: &[u8]| {
+            let mut h = [0; 32];
+            h.copy_from_slice(&Keccak256::digest(code));
+            h
+        };
+
+        let addr = create2_address(&H160::zero(), &[0; 32], &hash(&[0x00]));
+        assert_eq!(
+            "0x4D1A2e2bB4F88F0250f26Ffff098B0b30B26BF38",
+            evm_address_to_checksum(&addr)
+        );
+
+        let deployer = pnk!(evm_address_from_str(
+            "0x00000000000000000000000000000000deadbeef"
+        ));
+        let mut salt = [0; 32];
+        salt[28..].copy_from_slice(&[0xca, 0xfe, 0xba, 0xbe]);
+        let addr = create2_address(&deployer, &salt, &hash(&[0xde, 0xad, 0xbe, 0xef]));
+        assert_eq!(
+            "0x60f3f640a8508fC6a86d45DF051962668E1e8AC7",
+            evm_address_to_checksum(&addr)
+        );
+    }
+
+    #[test]
+    fn t_secret() {
+        let a = Secret::new([7u8; 32]);
+        assert_eq!(a, Secret::new([7u8; 32]));
+        assert_ne!(a, Secret::new([8u8; 32]));
+        assert_eq!("Secret(***)", format!("{:?}", a));
+    }
+
+    #[test]
+    fn t_ownership_challenge() {
+        let kp = new_keypair();
+        let nonce = "0123456789abcdef";
+
+        let sig = sign_ownership_challenge(&kp, nonce);
+        pnk!(verify_ownership_challenge(kp.get_pk_ref(), nonce, &sig));
+
+        assert!(
+            verify_ownership_challenge(kp.get_pk_ref(), "fedcba9876543210", &sig)
+                .is_err()
+        );
+        assert!(
+            verify_ownership_challenge(new_keypair().get_pk_ref(), nonce, &sig).is_err()
+        );
+        assert!(verify_ownership_challenge(kp.get_pk_ref(), nonce, "xx").is_err());
+    }
+
+    #[test]
+    fn t_sign_message() {
+        let kp = new_keypair();
+        let msg = b"login to findora at 2021-11-01";
+
+        let sig = sign_message(&kp, msg);
+        pnk!(verify_message(&kp.get_pk(), msg, &sig));
+
+        assert!(verify_message(&kp.get_pk(), b"another message", &sig).is_err());
+        assert!(verify_message(&new_keypair().get_pk(), msg, &sig).is_err());
+
+        // a raw signature over the message must not pass
+        let raw_sig = kp.get_sk_ref().sign(msg, kp.get_pk_ref());
+        assert!(verify_message(&kp.get_pk(), msg, &raw_sig).is_err());
+    }
+
+    #[test]
+    fn t_derive_tendermint_key() {
+        let phrase = generate_mnemonic_default();
+
+        let k = pnk!(derive_tendermint_key_from_mnemonic(&phrase, "en", 0));
+        assert_eq!(40, k.address.len());
+        assert_eq!(
+            k.address,
+            pnk!(derive_tendermint_key_from_mnemonic(&phrase, "en", 0)).address
+        );
+        assert_ne!(
+            k.address,
+            pnk!(derive_tendermint_key_from_mnemonic(&phrase, "en", 1)).address
+        );
+
+        // must not collide with the staking key
+        let staking_kp = pnk!(restore_keypair_from_mnemonic_default(&phrase));
+        assert_ne!(&k.pub_key[..], &staking_kp.get_pk().zei_to_bytes()[..]);
+
+        let json = k.to_priv_validator_key_json();
+        let v = pnk!(serde_json::from_str::<serde_json::Value>(&json));
+        assert_eq!(k.address, v["address"]);
+        assert_eq!(base64::encode(&k.pub_key), v["pub_key"]["value"]);
+        assert_eq!(
+            88,
+            v["priv_key"]["value"].as_str().unwrap().len(),
+            "64 bytes in base64"
+        );
+    }
+
     #[test]
     fn t_converts() {
         let pk = new_keypair().get_pk();